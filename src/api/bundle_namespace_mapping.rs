@@ -1,6 +1,8 @@
 use fleet_api_rs::fleet_bundle_namespace_mapping::{
-    BundleNamespaceMappingBundleSelector, BundleNamespaceMappingNamespaceSelector,
+    BundleNamespaceMappingBundleSelector, BundleNamespaceMappingBundleSelectorMatchExpressions,
+    BundleNamespaceMappingNamespaceSelector,
 };
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
 use kube::{
     Resource,
     api::{ObjectMeta, TypeMeta},
@@ -40,3 +42,20 @@ impl ResourceDiff for BundleNamespaceMapping {
         self != other
     }
 }
+
+/// Converts a label selector into the bundle selector of a `BundleNamespaceMapping`.
+pub(crate) fn to_bundle_selector(selector: &LabelSelector) -> BundleNamespaceMappingBundleSelector {
+    BundleNamespaceMappingBundleSelector {
+        match_labels: selector.match_labels.clone(),
+        match_expressions: selector.match_expressions.as_ref().map(|expressions| {
+            expressions
+                .iter()
+                .map(|e| BundleNamespaceMappingBundleSelectorMatchExpressions {
+                    key: e.key.clone(),
+                    operator: e.operator.clone(),
+                    values: e.values.clone(),
+                })
+                .collect()
+        }),
+    }
+}
@@ -2,9 +2,7 @@ use std::collections::BTreeMap;
 
 use cluster_api_rs::capi_cluster::{ClusterSpec, ClusterStatus};
 use fleet_api_rs::{
-    fleet_bundle_namespace_mapping::{
-        BundleNamespaceMappingBundleSelector, BundleNamespaceMappingNamespaceSelector,
-    },
+    fleet_bundle_namespace_mapping::BundleNamespaceMappingNamespaceSelector,
     fleet_clustergroup::{ClusterGroupSelector, ClusterGroupSpec},
 };
 use k8s_openapi::api::core::v1::Namespace;
@@ -16,12 +14,16 @@ use kube::{
 use rand::distr::{Alphanumeric, SampleString as _};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use super::{
-    bundle_namespace_mapping::BundleNamespaceMapping,
-    fleet_addon_config::ClusterConfig,
+    bundle_namespace_mapping::{BundleNamespaceMapping, to_bundle_selector},
+    fleet_addon_config::{ClusterConfig, DNS_SUBDOMAIN_MAX_LENGTH, truncate_with_hash},
     fleet_cluster,
-    fleet_clustergroup::{CLUSTER_CLASS_LABEL, CLUSTER_CLASS_NAMESPACE_LABEL, ClusterGroup},
+    fleet_clustergroup::{
+        CLUSTER_CLASS_LABEL, CLUSTER_CLASS_NAMESPACE_LABEL, CLUSTER_CLASS_VERSION_LABEL,
+        ClusterGroup,
+    },
 };
 
 #[cfg(feature = "agent-initiated")]
@@ -30,6 +32,17 @@ use super::fleet_cluster_registration_token::ClusterRegistrationToken;
 pub static FLEET_WORKSPACE_ANNOTATION: &str =
     "field.cattle.io/allow-fleetworkspace-creation-for-existing-namespace";
 
+pub static CLUSTER_NAME_LABEL: &str = "cluster.x-k8s.io/cluster-name";
+
+pub static CLUSTER_SOURCE_ANNOTATION: &str = "cluster-source.fleet.addons.cluster.x-k8s.io";
+pub static CLUSTER_SOURCE_UID_LABEL: &str = "cluster-source-uid.fleet.addons.cluster.x-k8s.io";
+
+pub static TOPOLOGY_VARIABLE_LABEL_PREFIX: &str = "topology.fleet.addons.cluster.x-k8s.io/";
+pub static TOPOLOGY_VERSION_LABEL: &str = "topology-version.fleet.addons.cluster.x-k8s.io";
+
+/// Maximum length of a label value, or of the name segment of a label key.
+const LABEL_VALUE_MAX_LENGTH: usize = 63;
+
 /// `ClusterProxy` defines the desired state of the CAPI Cluster.
 #[derive(CustomResource, Serialize, Deserialize, Clone, Debug, JsonSchema)]
 #[kube(
@@ -76,7 +89,10 @@ impl Cluster {
         Some(ClusterGroup {
             types: Some(TypeMeta::resource::<ClusterGroup>()),
             metadata: ObjectMeta {
-                name: Some(format!("{class}.{class_namespace}")),
+                name: Some(truncate_with_hash(
+                    &format!("{class}.{class_namespace}"),
+                    DNS_SUBDOMAIN_MAX_LENGTH,
+                )),
                 namespace: self.namespace(),
                 labels: labels.clone(),
                 owner_references: self.owner_ref(&()).into_iter().map(Into::into).collect(),
@@ -101,7 +117,11 @@ impl Cluster {
         let class = self.cluster_class_name();
         let ns = self.namespace().unwrap_or_default();
         let class_namespace = self.cluster_class_namespace().unwrap_or(ns);
-        let annotations = self.annotations().clone();
+        let annotations = {
+            let mut annotations = self.annotations().clone();
+            annotations.insert(CLUSTER_SOURCE_ANNOTATION.to_string(), self.source());
+            annotations
+        };
         let labels = {
             let mut labels = self.labels().clone();
             if let Some(class) = class {
@@ -111,6 +131,10 @@ impl Cluster {
                     class_namespace.to_string(),
                 );
             }
+            if let Some(uid) = self.uid() {
+                labels.insert(CLUSTER_SOURCE_UID_LABEL.to_string(), uid);
+            }
+            labels.extend(self.topology_labels(config));
             labels
         };
 
@@ -123,7 +147,7 @@ impl Cluster {
                     .set_owner_references
                     .is_some_and(|set| set)
                     .then_some(self.owner_ref(&()).into_iter().collect()),
-                name: config.apply_naming(self.name_any()).into(),
+                name: config.apply_naming(self).into(),
                 ..self.into()
             },
             #[cfg(feature = "agent-initiated")]
@@ -181,7 +205,11 @@ impl Cluster {
                 namespace: Some(class_namespace),
                 ..Default::default()
             },
-            bundle_selector: BundleNamespaceMappingBundleSelector::default(),
+            bundle_selector: config?
+                .bundle_selector
+                .as_ref()
+                .map(to_bundle_selector)
+                .unwrap_or_default(),
             namespace_selector: BundleNamespaceMappingNamespaceSelector {
                 match_labels,
                 ..Default::default()
@@ -223,13 +251,80 @@ impl Cluster {
         }
     }
 
+    /// Collects topology variables and version selected in the config as sanitized labels.
+    pub(crate) fn topology_labels(&self, config: &ClusterConfig) -> BTreeMap<String, String> {
+        let mut labels = BTreeMap::default();
+        let (Some(topology), Some(settings)) = (
+            self.spec.proxy.topology.as_ref(),
+            config.topology_labels.as_ref(),
+        ) else {
+            return labels;
+        };
+
+        if settings.version.is_some_and(|set| set) {
+            labels.insert(
+                TOPOLOGY_VERSION_LABEL.to_string(),
+                to_label_value(&topology.version),
+            );
+        }
+
+        let names = settings.variables.clone().unwrap_or_default();
+        for variable in topology
+            .variables
+            .iter()
+            .flatten()
+            .filter(|variable| names.contains(&variable.name))
+        {
+            let name = to_label_value(&variable.name);
+            let value = match &variable.value {
+                Value::Null => continue,
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            if name.is_empty() {
+                continue;
+            }
+
+            labels.insert(
+                format!("{TOPOLOGY_VARIABLE_LABEL_PREFIX}{name}"),
+                to_label_value(&value),
+            );
+        }
+
+        labels
+    }
+
+    /// Label with the Kubernetes minor version of the topology, e.g. `v1.31`, for version-aware class groups.
+    pub(crate) fn class_version_labels(&self) -> BTreeMap<String, String> {
+        let mut labels = BTreeMap::default();
+        let Some(topology) = self.spec.proxy.topology.as_ref() else {
+            return labels;
+        };
+
+        if let Some(minor) = minor_version(&topology.version) {
+            labels.insert(CLUSTER_CLASS_VERSION_LABEL.to_string(), minor);
+        }
+
+        labels
+    }
+
+    /// Reference to this cluster in the `<namespace>/<name>` form, recorded on generated Fleet clusters.
+    pub(crate) fn source(&self) -> String {
+        format!(
+            "{}/{}",
+            self.namespace().unwrap_or_default(),
+            self.name_any()
+        )
+    }
+
     pub(crate) fn cluster_class_namespace(&self) -> Option<String> {
         self.spec
             .proxy
             .topology
             .as_ref()?
             .class_ref
-            .namespace.clone()
+            .namespace
+            .clone()
     }
 
     pub(crate) fn cluster_class_name(&self) -> Option<String> {
@@ -237,3 +332,188 @@ impl Cluster {
         Some(topology.class_ref.name.clone())
     }
 }
+
+/// Returns the `v<major>.<minor>` part of a Kubernetes version, like `v1.31` for `v1.31.2+rke2r1`.
+pub(crate) fn minor_version(version: &str) -> Option<String> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let mut parts = version.split('.');
+    let major: u32 = parts.next()?.parse().ok()?;
+    let minor: u32 = parts.next()?.parse().ok()?;
+    Some(format!("v{major}.{minor}"))
+}
+
+/// Converts an arbitrary string into a valid label value.
+///
+/// Unsupported characters are replaced with `-`, the result is truncated to 63 characters,
+/// and non-alphanumeric characters are trimmed from both ends.
+pub(crate) fn to_label_value(value: &str) -> String {
+    let value: String = value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+
+    value
+        .trim_start_matches(|c: char| !c.is_ascii_alphanumeric())
+        .chars()
+        .take(LABEL_VALUE_MAX_LENGTH)
+        .collect::<String>()
+        .trim_end_matches(|c: char| !c.is_ascii_alphanumeric())
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use cluster_api_rs::capi_cluster::{
+        ClusterSpec, ClusterTopology, ClusterTopologyClassRef, ClusterTopologyVariables,
+    };
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+    use serde_json::json;
+
+    use crate::api::fleet_addon_config::{ClusterConfig, TopologyLabels};
+
+    use super::{
+        CLUSTER_CLASS_VERSION_LABEL, Cluster, ClusterProxy, TOPOLOGY_VARIABLE_LABEL_PREFIX,
+        TOPOLOGY_VERSION_LABEL, minor_version, to_label_value,
+    };
+
+    fn topology_cluster() -> Cluster {
+        let mut cluster = Cluster::new(
+            "test",
+            ClusterProxy {
+                proxy: ClusterSpec {
+                    topology: Some(ClusterTopology {
+                        class_ref: ClusterTopologyClassRef {
+                            name: "class".into(),
+                            namespace: None,
+                        },
+                        version: "v1.31.2".into(),
+                        variables: Some(vec![
+                            ClusterTopologyVariables {
+                                name: "region".into(),
+                                value: json!("eu-west-1"),
+                            },
+                            ClusterTopologyVariables {
+                                name: "replicas".into(),
+                                value: json!(3),
+                            },
+                            ClusterTopologyVariables {
+                                name: "ignored".into(),
+                                value: json!("value"),
+                            },
+                        ]),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            },
+        );
+        cluster.metadata.namespace = Some("default".into());
+        cluster
+    }
+
+    #[test]
+    fn test_bundle_ns_mapping_selector() {
+        let mut cluster = topology_cluster();
+        cluster
+            .spec
+            .proxy
+            .topology
+            .as_mut()
+            .unwrap()
+            .class_ref
+            .namespace = Some("classes".into());
+
+        let mapping = cluster
+            .to_bundle_ns_mapping(Some(&ClusterConfig::default()))
+            .unwrap();
+        assert_eq!(Some("classes".into()), mapping.metadata.namespace);
+        assert_eq!(None, mapping.bundle_selector.match_labels);
+
+        let config = ClusterConfig {
+            bundle_selector: Some(LabelSelector {
+                match_labels: Some([("approved".to_string(), "true".to_string())].into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mapping = cluster.to_bundle_ns_mapping(Some(&config)).unwrap();
+        assert_eq!(
+            Some(&"true".to_string()),
+            mapping
+                .bundle_selector
+                .match_labels
+                .as_ref()
+                .and_then(|labels| labels.get("approved"))
+        );
+    }
+
+    #[test]
+    fn test_to_label_value() {
+        assert_eq!(to_label_value("v1.31.2+rke2r1"), "v1.31.2-rke2r1");
+        assert_eq!(to_label_value(r#"{"a":"b"}"#), "a---b");
+        assert_eq!(to_label_value("-_value_-"), "value");
+        assert_eq!(to_label_value(&"a".repeat(70)), "a".repeat(63));
+        assert_eq!(
+            to_label_value(&format!("{}-b", "a".repeat(62))),
+            "a".repeat(62)
+        );
+        assert_eq!(to_label_value(""), "");
+    }
+
+    #[test]
+    fn test_topology_labels() {
+        let cluster = topology_cluster();
+        let config = ClusterConfig {
+            topology_labels: Some(TopologyLabels {
+                variables: Some(vec!["region".into(), "replicas".into(), "missing".into()]),
+                version: Some(true),
+            }),
+            ..Default::default()
+        };
+
+        let labels = cluster.to_cluster(Some(&config)).metadata.labels.unwrap();
+        assert_eq!(
+            labels.get(TOPOLOGY_VERSION_LABEL),
+            Some(&"v1.31.2".to_string())
+        );
+        assert_eq!(
+            labels.get(&format!("{TOPOLOGY_VARIABLE_LABEL_PREFIX}region")),
+            Some(&"eu-west-1".to_string())
+        );
+        assert_eq!(
+            labels.get(&format!("{TOPOLOGY_VARIABLE_LABEL_PREFIX}replicas")),
+            Some(&"3".to_string())
+        );
+        assert!(!labels.contains_key(&format!("{TOPOLOGY_VARIABLE_LABEL_PREFIX}ignored")));
+        assert!(!labels.contains_key(&format!("{TOPOLOGY_VARIABLE_LABEL_PREFIX}missing")));
+    }
+
+    #[test]
+    fn test_minor_version() {
+        assert_eq!(Some("v1.31".to_string()), minor_version("v1.31.2"));
+        assert_eq!(Some("v1.32".to_string()), minor_version("1.32.0+rke2r1"));
+        assert_eq!(None, minor_version("latest"));
+        assert_eq!(
+            Some(&"v1.31".to_string()),
+            topology_cluster()
+                .class_version_labels()
+                .get(CLUSTER_CLASS_VERSION_LABEL)
+        );
+    }
+
+    #[test]
+    fn test_topology_labels_disabled() {
+        let cluster = topology_cluster();
+        assert!(
+            cluster
+                .topology_labels(&ClusterConfig::default())
+                .is_empty()
+        );
+    }
+}
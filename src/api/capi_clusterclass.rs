@@ -9,9 +9,14 @@ mod prelude {
     pub use std::collections::BTreeMap;
 }
 use cluster_api_rs::capi_clusterclass::{ClusterClassSpec, ClusterClassStatus};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+use kube::ResourceExt as _;
 
 use self::prelude::*;
 
+/// Annotation with a JSON label selector of bundles shared with namespaces of clusters using the class.
+pub static BUNDLE_SELECTOR_ANNOTATION: &str = "bundle-selector.fleet.addons.cluster.x-k8s.io";
+
 /// `ClusterClassProxy` describes the desired state of the `ClusterClass`.
 #[derive(CustomResource, Serialize, Deserialize, Clone, Debug, JsonSchema)]
 #[kube(
@@ -26,3 +31,13 @@ pub struct ClusterClassProxy {
     #[serde(flatten)]
     pub proxy: ClusterClassSpec,
 }
+
+impl ClusterClass {
+    /// Bundle selector for `BundleNamespaceMappings` set on the class annotation, if any.
+    pub(crate) fn bundle_selector(&self) -> serde_json::Result<Option<LabelSelector>> {
+        self.annotations()
+            .get(BUNDLE_SELECTOR_ANNOTATION)
+            .map(|selector| serde_json::from_str(selector))
+            .transpose()
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
-use k8s_openapi::api::core::v1::Namespace;
+use k8s_openapi::api::core::v1::{Namespace, Secret};
+use kube::ResourceExt as _;
 
 // Trait for resources that can be compared
 pub(crate) trait ResourceDiff: kube::ResourceExt {
@@ -30,3 +31,14 @@ pub(crate) trait ResourceDiff: kube::ResourceExt {
 }
 
 impl ResourceDiff for Namespace {}
+
+impl ResourceDiff for Secret {
+    fn diff(&self, other: &Self) -> bool {
+        let annotations_equal = self
+            .annotations()
+            .iter()
+            .all(|(k, v)| other.annotations().get(k) == Some(v));
+
+        self.data != other.data || self.type_ != other.type_ || !annotations_equal
+    }
+}
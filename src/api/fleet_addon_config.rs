@@ -1,26 +1,34 @@
-use std::{fmt::Display, str::FromStr};
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
 
-use crate::api::comparable::ResourceDiff;
+use crate::api::{capi_cluster::Cluster, comparable::ResourceDiff};
 use educe::Educe;
 use fleet_api_rs::fleet_cluster::{ClusterAgentEnvVars, ClusterAgentTolerations};
 use k8s_openapi::{
-    api::core::v1::{ConfigMap, ObjectReference},
-    apimachinery::pkg::apis::meta::v1::{Condition, LabelSelector},
+    api::core::v1::{ConfigMap, Namespace, ObjectReference},
+    apimachinery::pkg::apis::meta::v1::{Condition, LabelSelector, Time},
 };
 use kube::{
-    CustomResource, KubeSchema, Resource,
+    CustomResource, KubeSchema, Resource, ResourceExt as _,
     api::{ObjectMeta, TypeMeta},
-    core::{ParseExpressionError, Selector},
+    core::{ParseExpressionError, Selector, SelectorExt as _},
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize, ser};
 use serde_with::{DisplayFromStr, serde_as};
 use serde_yaml::Value;
+use sha2::{Digest as _, Sha256};
+use thiserror::Error;
 
 pub const AGENT_NAMESPACE: &str = "fleet-addon-agent";
 pub const EXPERIMENTAL_OCI_STORAGE: &str = "EXPERIMENTAL_OCI_STORAGE";
 pub const EXPERIMENTAL_HELM_OPS: &str = "EXPERIMENTAL_HELM_OPS";
 
+/// Maximum length of a resource name used as a label value, like a Fleet `Cluster` name.
+pub const DNS_LABEL_MAX_LENGTH: usize = 63;
+/// Maximum length of a resource name, like a Fleet `ClusterGroup` name.
+pub const DNS_SUBDOMAIN_MAX_LENGTH: usize = 253;
+const HASH_LENGTH: usize = 8;
+
 /// This provides a config for fleet addon functionality
 #[derive(CustomResource, Deserialize, Serialize, Clone, Default, Debug, KubeSchema, PartialEq)]
 #[kube(
@@ -28,7 +36,12 @@ pub const EXPERIMENTAL_HELM_OPS: &str = "EXPERIMENTAL_HELM_OPS";
     group = "addons.cluster.x-k8s.io",
     version = "v1alpha1",
     status = "FleetAddonConfigStatus",
-    validation = "self.metadata.name == 'fleet-addon-config'"
+    validation = "self.metadata.name == 'fleet-addon-config'",
+    printcolumn = r#"{"name":"Installed", "type":"string", "jsonPath":".status.installedVersion"}"#,
+    printcolumn = r#"{"name":"Last Operation", "type":"string", "jsonPath":".status.history[-1:].operation"}"#,
+    printcolumn = r#"{"name":"Outcome", "type":"string", "jsonPath":".status.history[-1:].outcome"}"#,
+    printcolumn = r#"{"name":"Last Change", "type":"date", "jsonPath":".status.history[-1:].endTime"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
 )]
 #[serde(rename_all = "camelCase")]
 pub struct FleetAddonConfigSpec {
@@ -49,6 +62,10 @@ pub struct FleetAddonConfigSpec {
 
     // Fleet chart installation options
     pub install: Option<FleetInstall>,
+
+    /// Fleet chart removal options, applied when `install` is removed or the config is deleted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uninstall: Option<FleetUninstall>,
 }
 
 impl Default for FleetAddonConfig {
@@ -74,10 +91,121 @@ impl ResourceDiff for FleetAddonConfig {
 #[derive(Deserialize, Serialize, Clone, Default, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FleetAddonConfigStatus {
+    /// Generation of the config observed by the last reconcile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub observed_generation: Option<i64>,
     pub installed_version: Option<String>,
+    /// Chart version resolved from the `install.constraint`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_version: Option<String>,
     /// conditions represents the observations of a Fleet addon current state.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schemars(extend("x-kubernetes-list-type" = "map", "x-kubernetes-list-map-keys" = ["type"]))]
     pub conditions: Vec<Condition>,
+    /// Outcome of the last helm install, upgrade or uninstall operation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_operation: Option<OperationLog>,
+    /// Most recent Fleet chart operations, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<ChartOperationRecord>,
+    /// Import state of the CAPI clusters matched by the cluster selectors.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import_progress: Option<ImportProgressSummary>,
+}
+
+/// Import progress of the matched CAPI clusters, periodically updated by the cluster controller.
+#[derive(Deserialize, Serialize, Clone, Default, Debug, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportProgressSummary {
+    /// Clusters matched by the cluster and namespace selectors.
+    pub matched: i64,
+    /// Clusters imported into Fleet.
+    pub imported: i64,
+    /// Clusters waiting for the control plane, the kubeconfig secret or the first reconcile.
+    pub pending: i64,
+    /// Clusters which failed the last import attempt.
+    pub failed: i64,
+    /// Most recent import failures, newest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recent_failures: Vec<ImportFailure>,
+}
+
+/// Failed import attempt of a CAPI cluster.
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportFailure {
+    pub cluster: ObjectReference,
+    /// Truncated reconcile error.
+    pub message: String,
+    pub time: Time,
+}
+
+/// Number of chart operations kept in the status history.
+const HISTORY_LIMIT: usize = 10;
+/// Size limit of an error message recorded in the status history.
+const HISTORY_ERROR_LIMIT: usize = 512;
+
+impl FleetAddonConfigStatus {
+    /// Appends the operation to the history, dropping the oldest entries over the limit.
+    pub(crate) fn record_history(&mut self, mut record: ChartOperationRecord) {
+        if let Some(error) = record.error.as_mut()
+            && error.len() > HISTORY_ERROR_LIMIT
+        {
+            let mut end = HISTORY_ERROR_LIMIT;
+            while !error.is_char_boundary(end) {
+                end -= 1;
+            }
+            error.truncate(end);
+            error.push_str("...");
+        }
+
+        self.history.push(record);
+        let overflow = self.history.len().saturating_sub(HISTORY_LIMIT);
+        self.history.drain(..overflow);
+    }
+}
+
+/// Fleet chart install, upgrade or uninstall performed by the addon provider.
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartOperationRecord {
+    /// Chart name, `fleet` or `fleet-crd`.
+    pub chart: String,
+    /// Helm operation: `install`, `upgrade` or `uninstall`.
+    pub operation: String,
+    /// Installed version before the operation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_version: Option<String>,
+    /// Target version of the operation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_version: Option<String>,
+    pub start_time: Time,
+    pub end_time: Time,
+    pub outcome: OperationOutcome,
+    /// Truncated error message of a failed operation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub enum OperationOutcome {
+    Succeeded,
+    Failed,
+}
+
+/// Helm operation performed by the addon provider.
+#[derive(Deserialize, Serialize, Clone, Default, Debug, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationLog {
+    /// Helm command line.
+    pub command: String,
+    /// Name of the Job which performed the operation, when run in a Job.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job: Option<String>,
+    pub succeeded: bool,
+    /// Tail of the operation output.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub logs: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, PartialEq)]
@@ -91,6 +219,12 @@ pub struct ClusterClassConfig {
     /// If is not set, resources will only be re-created in case of removal.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub patch_resource: Option<bool>,
+
+    /// Additionally create `ClusterGroups` for each `ClusterClass` and Kubernetes minor version
+    /// used by the imported clusters. The minor version is set on Fleet clusters under
+    /// the `clusterclass-version.fleet.addons.cluster.x-k8s.io` label.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_groups: Option<bool>,
 }
 
 impl Default for ClusterClassConfig {
@@ -98,6 +232,7 @@ impl Default for ClusterClassConfig {
         Self {
             patch_resource: Some(true),
             set_owner_references: Some(true),
+            version_groups: None,
         }
     }
 }
@@ -139,6 +274,25 @@ pub struct ClusterConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub agent_env_vars: Option<Vec<ClusterAgentEnvVars>>,
 
+    /// Fleet workspace mappings for CAPI cluster namespaces. The first matching mapping
+    /// places the Fleet cluster into the target workspace instead of the cluster namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_mappings: Option<Vec<WorkspaceMapping>>,
+
+    /// Generators of Fleet `ClusterGroups` for each distinct value of a label on imported clusters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label_groups: Option<Vec<LabelGroup>>,
+
+    /// Topology values of the CAPI cluster to expose as Fleet cluster labels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topology_labels: Option<TopologyLabels>,
+
+    /// Label selector of bundles shared from the `ClusterClass` namespace into the cluster namespace
+    /// with the `BundleNamespaceMapping`. All bundles are shared if not set. Can be overridden
+    /// per `ClusterClass` with the `bundle-selector.fleet.addons.cluster.x-k8s.io` annotation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_selector: Option<LabelSelector>,
+
     /// Import settings for the CAPI cluster. Allows to import clusters based on a set of labels,
     /// set on the cluster or the namespace.
     #[serde(flatten)]
@@ -165,16 +319,20 @@ impl ResourceDiff for FleetSettings {
     }
 }
 
+/// `FleetSettingsSpec` is the data of the `fleet-controller` `ConfigMap`.
 #[serde_as]
-#[derive(Serialize, Deserialize, Default, Clone, Debug, Educe)]
+#[derive(Serialize, Deserialize, Default, Clone, Debug, Educe, JsonSchema)]
 #[educe(PartialEq)]
 pub struct FleetSettingsSpec {
+    /// Fleet chart values, encoded as YAML.
     #[serde(default)]
     #[serde_as(as = "DisplayFromStr")]
+    #[schemars(with = "String")]
     pub fleet: FleetChartValues,
 
     #[educe(PartialEq(ignore))]
     #[serde(flatten)]
+    #[schemars(with = "serde_json::Map<String, serde_json::Value>")]
     pub other: Value,
 }
 
@@ -202,6 +360,21 @@ impl FleetAddonConfigSpec {
     pub(crate) fn bootstrap(&self) -> Option<bool> {
         self.config.as_ref()?.bootstrap_local_cluster
     }
+
+    /// Returns true if Fleet releases should be uninstalled on removal.
+    pub(crate) fn uninstall_enabled(&self) -> bool {
+        self.uninstall
+            .as_ref()
+            .is_some_and(|u| u.policy == UninstallPolicy::Uninstall)
+    }
+
+    /// Returns true if Fleet should be uninstalled even if imported clusters remain.
+    pub(crate) fn uninstall_forced(&self) -> bool {
+        self.uninstall
+            .as_ref()
+            .and_then(|u| u.force)
+            .unwrap_or_default()
+    }
 }
 
 impl ClusterConfig {
@@ -242,9 +415,44 @@ impl ClusterConfig {
         self.agent_initiated.filter(|&set| set).is_some()
     }
 
-    pub(crate) fn apply_naming(&self, name: String) -> String {
+    pub(crate) fn apply_naming(&self, cluster: &Cluster) -> String {
+        truncate_with_hash(&self.untruncated_naming(cluster), DNS_LABEL_MAX_LENGTH)
+    }
+
+    /// Fleet cluster name before truncation, used by clusters imported before names were truncated.
+    pub(crate) fn untruncated_naming(&self, cluster: &Cluster) -> String {
         let strategy = self.naming.clone().unwrap_or_default();
-        strategy.apply(name.clone().into()).unwrap_or(name)
+        strategy.render_untruncated(
+            &cluster.name_any(),
+            &cluster.namespace().unwrap_or_default(),
+            &cluster.uid().unwrap_or_default(),
+        )
+    }
+
+    /// Returns the Fleet workspace for clusters in the namespace, if the namespace is mapped.
+    pub(crate) fn workspace(
+        &self,
+        namespace: &Namespace,
+    ) -> Result<Option<String>, ParseExpressionError> {
+        for mapping in self.workspace_mappings.iter().flatten() {
+            if mapping.matches(namespace)? {
+                return Ok(Some(mapping.workspace.clone()));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns configured label group generators.
+    pub(crate) fn label_groups(&self) -> Vec<LabelGroup> {
+        self.label_groups.clone().unwrap_or_default()
+    }
+
+    /// Checks if any workspace mapping is configured.
+    pub(crate) fn workspace_mapping_enabled(&self) -> bool {
+        self.workspace_mappings
+            .as_ref()
+            .is_some_and(|mappings| !mappings.is_empty())
     }
 
     pub(crate) fn apply_class_group(&self) -> bool {
@@ -253,12 +461,128 @@ impl ClusterConfig {
 }
 
 /// `NamingStrategy` is controlling Fleet cluster naming
+///
+/// Names exceeding 63 characters are truncated and suffixed with a hash of the full name.
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default, PartialEq)]
 pub struct NamingStrategy {
     /// Specify a prefix for the Cluster name, applied to created Fleet cluster
     pub prefix: Option<String>,
     /// Specify a suffix for the Cluster name, applied to created Fleet cluster
     pub suffix: Option<String>,
+    /// Specify a template for the Cluster name, applied before prefix and suffix.
+    /// Supported placeholders are `{name}`, `{namespace}`, `{uid}` and `{hash}`,
+    /// where `{hash}` is a short stable hash of the Cluster namespace and name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+/// `TopologyLabels` controls which values from the CAPI cluster topology are set as Fleet cluster labels.
+///
+/// Values are sanitized to form a valid label value.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TopologyLabels {
+    /// Names of the topology variables to set as labels. Each variable is stored
+    /// under the `topology.fleet.addons.cluster.x-k8s.io/<name>` label key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variables: Option<Vec<String>>,
+
+    /// Set the topology Kubernetes version under the `topology-version.fleet.addons.cluster.x-k8s.io` label key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<bool>,
+}
+
+/// `LabelGroup` generates a Fleet `ClusterGroup` for each distinct value of a label observed on imported Fleet clusters.
+///
+/// Groups are created in each namespace containing matching clusters, and removed once the value is no longer present.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelGroup {
+    /// Label key to group imported clusters by, e.g. `region` or `env`.
+    pub label: String,
+
+    /// Prefix for the generated `ClusterGroup` names. Defaults to the label name followed by `-`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+}
+
+impl LabelGroup {
+    /// Returns the `ClusterGroup` name for a label value.
+    ///
+    /// Values changed by the name sanitization, like `Prod` or `eu_west`, are suffixed
+    /// with a hash of the label and the value, so they don't share a group with `prod` or `eu-west`.
+    pub(crate) fn group_name(&self, value: &str) -> String {
+        let sanitized = value
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '.'));
+        self.name(value, !sanitized)
+    }
+
+    /// Returns the `ClusterGroup` name for a label value, always suffixed with a hash of the
+    /// label and the value. Used when the names of distinct label values collide.
+    pub(crate) fn unique_group_name(&self, value: &str) -> String {
+        self.name(value, true)
+    }
+
+    fn name(&self, value: &str, hashed: bool) -> String {
+        let prefix = self.prefix.clone().unwrap_or_else(|| {
+            let name = self.label.rsplit('/').next().unwrap_or(&self.label);
+            format!("{name}-")
+        });
+
+        let name: String = format!("{prefix}{value}")
+            .to_lowercase()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '.') {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let name = name.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+        let name = if hashed {
+            format!("{name}-{}", short_hash(&format!("{}={value}", self.label)))
+        } else {
+            name.to_string()
+        };
+
+        truncate_with_hash(&name, DNS_SUBDOMAIN_MAX_LENGTH)
+    }
+}
+
+/// `WorkspaceMapping` places Fleet clusters for CAPI clusters from matching namespaces into a shared Fleet workspace.
+///
+/// The cluster kubeconfig secret is replicated into the workspace namespace and kept in sync.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceMapping {
+    /// Target Fleet workspace namespace.
+    pub workspace: String,
+
+    /// Names of the CAPI cluster namespaces mapped to the workspace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespaces: Option<Vec<String>>,
+
+    /// Label selector for the CAPI cluster namespaces mapped to the workspace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace_selector: Option<LabelSelector>,
+}
+
+impl WorkspaceMapping {
+    /// Checks if the namespace is mapped to the workspace by name or by the namespace selector.
+    pub(crate) fn matches(&self, namespace: &Namespace) -> Result<bool, ParseExpressionError> {
+        let name = namespace.name_any();
+        if self.namespaces.iter().flatten().any(|ns| *ns == name) {
+            return Ok(true);
+        }
+
+        match self.namespace_selector.clone() {
+            Some(selector) => Ok(Selector::try_from(selector)?.matches(namespace.labels())),
+            None => Ok(false),
+        }
+    }
 }
 
 impl Default for ClusterConfig {
@@ -275,6 +599,10 @@ impl Default for ClusterConfig {
             patch_resource: Some(true),
             agent_env_vars: None,
             agent_tolerations: None,
+            topology_labels: None,
+            bundle_selector: None,
+            workspace_mappings: None,
+            label_groups: None,
         }
     }
 }
@@ -291,6 +619,9 @@ pub struct FleetConfig {
     /// Enable auto-installation of a fleet agent in the local cluster.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bootstrap_local_cluster: Option<bool>,
+    /// Options for inferring the local API server URL with `server.inferLocal`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_inference: Option<ServerInference>,
 }
 
 impl Default for FleetConfig {
@@ -299,31 +630,122 @@ impl Default for FleetConfig {
             server: Option::default(),
             feature_gates: Some(FeatureGates::default()),
             bootstrap_local_cluster: None,
+            server_inference: None,
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerInference {
+    /// Preferred address family of the `default/kubernetes` service endpoints.
+    /// Endpoints of other families are used only if no preferred endpoint is available.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address_family: Option<AddressFamily>,
+    /// Prefer the `controlPlaneEndpoint` from the `kube-system/kubeadm-config` ConfigMap
+    /// over the service endpoint addresses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kubeadm_endpoint: Option<bool>,
+}
+
+/// Address family of an `EndpointSlice`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub enum AddressFamily {
+    IPv4,
+    IPv6,
+}
+
+impl AddressFamily {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::IPv4 => "IPv4",
+            Self::IPv6 => "IPv6",
         }
     }
 }
 
+/// Fleet feature flag known to the addon provider.
+pub struct FeatureFlag {
+    /// Name of the Fleet controller environment variable.
+    pub name: &'static str,
+    /// Value applied unless configured otherwise.
+    pub default: bool,
+}
+
+/// Registry of known Fleet feature flags, in the order they are applied.
+pub const FEATURE_FLAGS: &[FeatureFlag] = &[
+    FeatureFlag {
+        name: EXPERIMENTAL_HELM_OPS,
+        default: true,
+    },
+    FeatureFlag {
+        name: EXPERIMENTAL_OCI_STORAGE,
+        default: true,
+    },
+];
+
+pub type FeatureFlagResult<T, E = FeatureFlagError> = std::result::Result<T, E>;
+
+#[derive(Error, Debug)]
+pub enum FeatureFlagError {
+    #[error("Feature flag `{0}` is not a valid environment variable name")]
+    InvalidName(String),
+}
+
 /// Feature toggles for enabling or disabling experimental functionality.
 /// This struct controls access to specific experimental features.
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FeatureGates {
     /// Enables experimental OCI  storage support.
+    #[serde(default = "oci_storage_default")]
     pub experimental_oci_storage: bool,
 
     /// Enables experimental Helm operations support.
+    #[serde(default = "helm_ops_default")]
     pub experimental_helm_ops: bool,
 
+    /// Fleet feature flags by environment variable name, like `EXPERIMENTAL_OCI_STORAGE`.
+    /// Takes precedence over the individual toggles. Flags not recognized by the
+    /// provider are passed to Fleet as is.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flags: Option<BTreeMap<String, bool>>,
+
     // Enables syncing of feature gates to a ConfigMap.
     pub config_map: Option<FeaturesConfigMap>,
 }
 
+/// Registry default of a known feature flag, unknown flags are disabled.
+fn flag_default(name: &str) -> bool {
+    FEATURE_FLAGS
+        .iter()
+        .find(|flag| flag.name == name)
+        .is_some_and(|flag| flag.default)
+}
+
+fn oci_storage_default() -> bool {
+    flag_default(EXPERIMENTAL_OCI_STORAGE)
+}
+
+fn helm_ops_default() -> bool {
+    flag_default(EXPERIMENTAL_HELM_OPS)
+}
+
 impl Display for FeatureGates {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let oci = self.experimental_oci_storage;
-        let helm_ops = self.experimental_helm_ops;
         let config_map = self.config_map_ref();
-        f.write_str(&format!("ref={config_map:#?}, oci={oci}, helm={helm_ops}"))
+        let flags = self
+            .resolved_flags()
+            .map(|flags| {
+                flags
+                    .iter()
+                    .map(|(name, value)| format!("{name}={value}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_else(|e| e.to_string());
+        f.write_str(&format!("ref={config_map:#?}, flags=[{flags}]"))
     }
 }
 
@@ -333,41 +755,69 @@ impl FeatureGates {
         self.config_map.as_ref()?.reference.as_ref()
     }
 
+    /// Resolves the value of every feature flag, in the order they should be applied.
+    ///
+    /// Known flags start with the registry default, overridden by the individual toggles,
+    /// and then by the `flags` map. Unknown flags from the map follow in name order.
+    pub(crate) fn resolved_flags(&self) -> FeatureFlagResult<Vec<(String, bool)>> {
+        let mut flags = self.flags.clone().unwrap_or_default();
+        if let Some(name) = flags.keys().find(|name| !valid_flag_name(name)) {
+            return Err(FeatureFlagError::InvalidName(name.clone()));
+        }
+
+        let mut resolved: Vec<(String, bool)> = FEATURE_FLAGS
+            .iter()
+            .map(|flag| {
+                let value = match flag.name {
+                    EXPERIMENTAL_HELM_OPS => self.experimental_helm_ops,
+                    EXPERIMENTAL_OCI_STORAGE => self.experimental_oci_storage,
+                    _ => flag.default,
+                };
+                let value = flags.remove(flag.name).unwrap_or(value);
+                (flag.name.to_string(), value)
+            })
+            .collect();
+        resolved.extend(flags);
+
+        Ok(resolved)
+    }
+
     /// Merge the feature gates environment variables with a provided optional input.
-    pub(crate) fn merge_features(&self, settings: &mut FleetSettingsSpec) {
-        // Sync the feature flags to the map.
+    ///
+    /// This is the only place feature flags are converted into Fleet chart values,
+    /// shared by the `ConfigMap` sync and the helm install.
+    pub(crate) fn merge_features(&self, settings: &mut FleetSettingsSpec) -> FeatureFlagResult<()> {
         let env_map = settings.fleet.extra_env.get_or_insert_default();
 
-        match env_map
-            .iter_mut()
-            .find(|env| env.name == EXPERIMENTAL_HELM_OPS)
-        {
-            Some(helm_ops) => helm_ops.value = self.experimental_helm_ops.to_string(),
-            None => env_map.push(EnvironmentVariable {
-                name: EXPERIMENTAL_HELM_OPS.to_string(),
-                value: self.experimental_helm_ops.to_string(),
-            }),
+        for (name, value) in self.resolved_flags()? {
+            match env_map.iter_mut().find(|env| env.name == name) {
+                Some(env) => env.value = value.to_string(),
+                None => env_map.push(EnvironmentVariable {
+                    name,
+                    value: value.to_string(),
+                }),
+            }
         }
 
-        match env_map
-            .iter_mut()
-            .find(|env| env.name == EXPERIMENTAL_OCI_STORAGE)
-        {
-            Some(helm_ops) => helm_ops.value = self.experimental_oci_storage.to_string(),
-            None => env_map.push(EnvironmentVariable {
-                name: EXPERIMENTAL_OCI_STORAGE.to_string(),
-                value: self.experimental_oci_storage.to_string(),
-            }),
-        }
+        Ok(())
     }
 }
 
+fn valid_flag_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
 impl Default for FeatureGates {
     fn default() -> Self {
         Self {
-            // Unless is set otherwise, these features are enabled by CAAPF
-            experimental_oci_storage: true,
-            experimental_helm_ops: true,
+            // Unless is set otherwise, the registry defaults apply
+            experimental_oci_storage: oci_storage_default(),
+            experimental_helm_ops: helm_ops_default(),
+            flags: None,
             config_map: None,
         }
     }
@@ -384,16 +834,17 @@ pub struct FeaturesConfigMap {
 }
 
 /// `FleetChartValues` represents Fleet chart values.
-#[derive(Clone, Default, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FleetChartValues {
     pub extra_env: Option<Vec<EnvironmentVariable>>,
     #[serde(flatten)]
+    #[schemars(with = "serde_json::Map<String, serde_json::Value>")]
     pub other: Value,
 }
 
 /// `EnvironmentVariable` is a simple name/value pair.
-#[derive(Clone, Default, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct EnvironmentVariable {
     pub name: String,
@@ -406,6 +857,49 @@ pub struct FleetInstall {
     /// Chart version to install
     #[serde(flatten)]
     pub install_version: Install,
+
+    /// Maintenance windows for Fleet upgrades. Upgrades outside of a window are deferred
+    /// until the next window starts. Initial installation is not restricted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maintenance_windows: Option<Vec<MaintenanceWindow>>,
+}
+
+/// `FleetUninstall` configures removal of the Fleet releases installed by the provider.
+#[derive(Clone, Default, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FleetUninstall {
+    /// Policy for the `fleet` and `fleet-crd` releases. Releases are retained by default.
+    #[serde(default)]
+    pub policy: UninstallPolicy,
+
+    /// Uninstall even if Fleet clusters imported by the provider remain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub force: Option<bool>,
+}
+
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub enum UninstallPolicy {
+    /// Keep Fleet installed.
+    #[default]
+    Retain,
+
+    /// Uninstall Fleet once no imported clusters remain.
+    Uninstall,
+}
+
+/// `MaintenanceWindow` is a recurring period when Fleet upgrades are allowed.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceWindow {
+    /// Cron schedule of the window start, like `0 2 * * SAT`.
+    pub schedule: String,
+
+    /// Window duration, like `2h` or `1h30m`.
+    pub duration: String,
+
+    /// IANA time zone of the schedule, like `Europe/Berlin`. Defaults to `UTC`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
@@ -416,13 +910,17 @@ pub enum Install {
 
     /// Use specific version to install
     Version(String),
+
+    /// Install the highest chart version satisfying a semver constraint, like `~0.12` or `>=0.11 <0.13`.
+    /// Upgrades stay within the constraint.
+    Constraint(String),
 }
 
 impl Install {
     /// Perform version normalization for comparison with `helm search` `app_version` output
     pub(crate) fn normalized(self) -> Self {
         match self {
-            Install::FollowLatest(_) => self,
+            Install::FollowLatest(_) | Install::Constraint(_) => self,
             Install::Version(version) => {
                 Install::Version(version.strip_prefix("v").unwrap_or(&version).into())
             }
@@ -438,6 +936,7 @@ impl Default for Install {
 
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
+#[allow(clippy::large_enum_variant)]
 pub enum Server {
     InferLocal(bool),
     Custom(InstallOptions),
@@ -447,10 +946,43 @@ pub enum Server {
 #[serde(rename_all = "camelCase")]
 pub struct InstallOptions {
     pub api_server_ca_config_ref: Option<ObjectReference>,
+    /// Reference to a Secret containing the CA certificate for the API server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_server_ca_secret_ref: Option<ObjectReference>,
+    /// Key of the CA certificate in the referenced ConfigMap or Secret. Defaults to `ca.crt`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_server_ca_key: Option<String>,
+    /// Inline PEM encoded CA certificates for the API server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_server_ca: Option<String>,
     pub api_server_url: Option<String>,
 }
 
 impl NamingStrategy {
+    /// Renders the Fleet cluster name for a Cluster, keeping it within the 63 characters limit.
+    #[must_use]
+    pub fn render(&self, name: &str, namespace: &str, uid: &str) -> String {
+        truncate_with_hash(
+            &self.render_untruncated(name, namespace, uid),
+            DNS_LABEL_MAX_LENGTH,
+        )
+    }
+
+    /// Renders the Fleet cluster name for a Cluster, without the length limit.
+    #[must_use]
+    pub fn render_untruncated(&self, name: &str, namespace: &str, uid: &str) -> String {
+        let name = match &self.template {
+            Some(template) => template
+                .replace("{name}", name)
+                .replace("{namespace}", namespace)
+                .replace("{uid}", uid)
+                .replace("{hash}", &short_hash(&format!("{namespace}/{name}"))),
+            None => name.to_string(),
+        };
+
+        self.apply(name.clone().into()).unwrap_or(name)
+    }
+
     #[must_use]
     pub fn apply(&self, name: Option<String>) -> Option<String> {
         name.map(|name| match &self.prefix {
@@ -464,6 +996,26 @@ impl NamingStrategy {
     }
 }
 
+/// Returns a short stable hex hash of the input.
+pub(crate) fn short_hash(input: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(input.as_bytes()));
+    digest[..HASH_LENGTH].to_string()
+}
+
+/// Truncates a name to the maximum length, replacing the tail with a hash of the full name.
+///
+/// Names within the limit are returned unchanged.
+pub(crate) fn truncate_with_hash(name: &str, max_length: usize) -> String {
+    if name.len() <= max_length {
+        return name.to_string();
+    }
+
+    let hash = short_hash(name);
+    let head: String = name.chars().take(max_length - HASH_LENGTH - 1).collect();
+    let head = head.trim_end_matches(|c: char| !c.is_ascii_alphanumeric());
+    format!("{head}-{hash}")
+}
+
 /// Selectors is controlling Fleet import strategy settings.
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -517,6 +1069,15 @@ impl FleetAddonConfig {
             .is_some()
     }
 
+    // Check for clusterClass version groups setting. Evaluates to false if disabled.
+    pub(crate) fn cluster_class_version_groups_enabled(&self) -> bool {
+        self.spec
+            .cluster_class
+            .as_ref()
+            .and_then(|c| c.version_groups)
+            .is_some_and(|enabled| enabled)
+    }
+
     // Check for general clusterClass patching setting.
     pub(crate) fn cluster_class_patch_enabled(&self) -> bool {
         self.spec
@@ -533,8 +1094,18 @@ impl FleetAddonConfig {
 mod tests {
     use std::str::FromStr;
 
+    use jiff::Timestamp;
+    use k8s_openapi::{
+        api::core::v1::Namespace,
+        apimachinery::pkg::apis::meta::v1::{LabelSelector, Time},
+    };
+    use kube::api::ObjectMeta;
+
     use crate::api::fleet_addon_config::{
-        FeatureGates, FleetChartValues, FleetSettingsSpec, NamingStrategy,
+        ChartOperationRecord, ClusterConfig, DNS_LABEL_MAX_LENGTH, FEATURE_FLAGS, FeatureFlagError,
+        FeatureGates, FleetAddonConfigStatus, FleetChartValues, FleetSettingsSpec,
+        HISTORY_ERROR_LIMIT, HISTORY_LIMIT, LabelGroup, NamingStrategy, OperationOutcome,
+        WorkspaceMapping, short_hash, truncate_with_hash,
     };
 
     #[tokio::test]
@@ -544,6 +1115,7 @@ mod tests {
             NamingStrategy {
                 prefix: "prefix".to_string().into(),
                 suffix: "suffix".to_string().into(),
+                ..Default::default()
             }
             .apply("test".to_string().into())
         );
@@ -579,11 +1151,147 @@ mod tests {
             NamingStrategy {
                 prefix: "prefix".to_string().into(),
                 suffix: "suffix".to_string().into(),
+                ..Default::default()
             }
             .apply(None)
         );
     }
 
+    #[tokio::test]
+    async fn test_naming_template() {
+        let strategy = NamingStrategy {
+            template: "{namespace}-{name}".to_string().into(),
+            prefix: "capi-".to_string().into(),
+            ..Default::default()
+        };
+        assert_eq!("capi-ns-test", strategy.render("test", "ns", "uid"));
+
+        let strategy = NamingStrategy {
+            template: "{name}-{uid}".to_string().into(),
+            ..Default::default()
+        };
+        assert_eq!("test-1234", strategy.render("test", "ns", "1234"));
+
+        let strategy = NamingStrategy {
+            template: "{name}-{hash}".to_string().into(),
+            ..Default::default()
+        };
+        let hashed = strategy.render("test", "ns", "uid");
+        assert_eq!(format!("test-{}", short_hash("ns/test")), hashed);
+        assert_eq!(hashed, strategy.render("test", "ns", "other-uid"));
+        assert_ne!(hashed, strategy.render("test", "other-ns", "uid"));
+
+        // Long names are truncated, the full name is kept for the previously imported clusters
+        let long = "a".repeat(70);
+        let strategy = NamingStrategy::default();
+        assert_eq!(63, strategy.render(&long, "ns", "uid").len());
+        assert_eq!(long, strategy.render_untruncated(&long, "ns", "uid"));
+    }
+
+    #[tokio::test]
+    async fn test_naming_truncate() {
+        let long_name = "a".repeat(70);
+        let name = NamingStrategy::default().render(&long_name, "ns", "uid");
+        assert_eq!(DNS_LABEL_MAX_LENGTH, name.len());
+        assert_eq!(
+            format!("{}-{}", "a".repeat(54), short_hash(&long_name)),
+            name
+        );
+
+        let other = NamingStrategy::default().render(&"a".repeat(71), "ns", "uid");
+        assert_ne!(name, other);
+
+        let dotted = format!("{}.{}", "a".repeat(53), "b".repeat(20));
+        let name = truncate_with_hash(&dotted, DNS_LABEL_MAX_LENGTH);
+        assert_eq!(format!("{}-{}", "a".repeat(53), short_hash(&dotted)), name);
+        assert_eq!("short", truncate_with_hash("short", DNS_LABEL_MAX_LENGTH));
+    }
+
+    #[tokio::test]
+    async fn test_label_group_name() {
+        let group = LabelGroup {
+            label: "topology.fleet.addons.cluster.x-k8s.io/region".into(),
+            prefix: None,
+        };
+        assert_eq!("region-eu-west-1", group.group_name("eu-west-1"));
+        assert_eq!(
+            format!(
+                "region-eu-west-{}",
+                short_hash("topology.fleet.addons.cluster.x-k8s.io/region=EU_west")
+            ),
+            group.group_name("EU_west")
+        );
+
+        let group = LabelGroup {
+            label: "env".into(),
+            prefix: Some("Env.".into()),
+        };
+        assert_eq!("env.prod", group.group_name("prod"));
+        assert_eq!(
+            format!("env.team-a-{}", short_hash("env=team_a")),
+            group.group_name("team_a")
+        );
+
+        // Values differing only by case get distinct names
+        assert_ne!(group.group_name("Prod"), group.group_name("prod"));
+        assert_eq!(
+            format!("env.prod-{}", short_hash("env=prod")),
+            group.unique_group_name("prod")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_workspace_mapping() {
+        let namespace = |name: &str, labels: &[(&str, &str)]| Namespace {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                labels: Some(
+                    labels
+                        .iter()
+                        .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                        .collect(),
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let config = ClusterConfig {
+            workspace_mappings: Some(vec![
+                WorkspaceMapping {
+                    workspace: "by-name".into(),
+                    namespaces: Some(vec!["tenant-a".into()]),
+                    namespace_selector: None,
+                },
+                WorkspaceMapping {
+                    workspace: "by-label".into(),
+                    namespaces: None,
+                    namespace_selector: Some(LabelSelector {
+                        match_labels: Some([("tenant".to_string(), "true".to_string())].into()),
+                        ..Default::default()
+                    }),
+                },
+            ]),
+            ..Default::default()
+        };
+
+        assert!(config.workspace_mapping_enabled());
+        assert_eq!(
+            Some("by-name".to_string()),
+            config
+                .workspace(&namespace("tenant-a", &[("tenant", "true")]))
+                .unwrap()
+        );
+        assert_eq!(
+            Some("by-label".to_string()),
+            config
+                .workspace(&namespace("tenant-b", &[("tenant", "true")]))
+                .unwrap()
+        );
+        assert_eq!(None, config.workspace(&namespace("other", &[])).unwrap());
+        assert!(!ClusterConfig::default().workspace_mapping_enabled());
+    }
+
     #[tokio::test]
     async fn test_sync_config_map() {
         let want_fleet_data = r"extraEnv:
@@ -610,7 +1318,7 @@ foo:
         };
         let feature_gates = FeatureGates::default();
 
-        feature_gates.merge_features(&mut data);
+        feature_gates.merge_features(&mut data).unwrap();
 
         assert_eq!(want_fleet_data.to_string(), data.fleet.to_string());
     }
@@ -627,11 +1335,91 @@ foo:
         let feature_gates = FeatureGates {
             experimental_oci_storage: false,
             experimental_helm_ops: false,
+            flags: None,
             config_map: None,
         };
 
-        feature_gates.merge_features(&mut data);
+        feature_gates.merge_features(&mut data).unwrap();
 
         assert_eq!(want_fleet_data.to_string(), data.fleet.to_string());
     }
+
+    #[test]
+    fn test_merge_feature_flags() {
+        let want_fleet_data = r"extraEnv:
+- name: EXPERIMENTAL_OCI_STORAGE
+  value: 'true'
+- name: EXPERIMENTAL_HELM_OPS
+  value: 'false'
+- name: EXPERIMENTAL_COPY_RESOURCES_DOWNSTREAM
+  value: 'true'
+";
+        let fleet_data = r"extraEnv:
+- name: EXPERIMENTAL_OCI_STORAGE
+  value: 'false'
+";
+        let mut data = FleetSettingsSpec {
+            fleet: FleetChartValues::from_str(fleet_data).unwrap(),
+            ..Default::default()
+        };
+        let feature_gates = FeatureGates {
+            flags: Some(
+                [
+                    ("EXPERIMENTAL_HELM_OPS".to_string(), false),
+                    ("EXPERIMENTAL_COPY_RESOURCES_DOWNSTREAM".to_string(), true),
+                ]
+                .into(),
+            ),
+            ..Default::default()
+        };
+
+        feature_gates.merge_features(&mut data).unwrap();
+
+        assert_eq!(want_fleet_data.to_string(), data.fleet.to_string());
+
+        let invalid = FeatureGates {
+            flags: Some([("experimental-helm-ops".to_string(), true)].into()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            invalid.merge_features(&mut FleetSettingsSpec::default()),
+            Err(FeatureFlagError::InvalidName(_))
+        ));
+    }
+
+    #[test]
+    fn test_feature_flag_registry_defaults() {
+        let defaults: Vec<(String, bool)> = FEATURE_FLAGS
+            .iter()
+            .map(|flag| (flag.name.to_string(), flag.default))
+            .collect();
+
+        let unset: FeatureGates = serde_json::from_str("{}").unwrap();
+        assert_eq!(defaults, unset.resolved_flags().unwrap());
+        assert_eq!(defaults, FeatureGates::default().resolved_flags().unwrap());
+    }
+
+    #[test]
+    fn test_record_history() {
+        let mut status = FleetAddonConfigStatus::default();
+        for version in 0..=HISTORY_LIMIT {
+            status.record_history(ChartOperationRecord {
+                chart: "fleet".into(),
+                operation: "upgrade".into(),
+                from_version: None,
+                to_version: Some(format!("0.{version}.0")),
+                start_time: Time(Timestamp::now()),
+                end_time: Time(Timestamp::now()),
+                outcome: OperationOutcome::Failed,
+                error: Some("é".repeat(HISTORY_ERROR_LIMIT)),
+            });
+        }
+
+        assert_eq!(HISTORY_LIMIT, status.history.len());
+        assert_eq!(Some("0.1.0"), status.history[0].to_version.as_deref());
+        let last = status.history.last().unwrap();
+        assert_eq!(Some(format!("0.{HISTORY_LIMIT}.0")), last.to_version);
+        let error = last.error.as_deref().unwrap();
+        assert!(error.ends_with("...") && error.len() <= HISTORY_ERROR_LIMIT + 3);
+    }
 }
@@ -2,9 +2,11 @@ use fleet_api_rs::fleet_cluster::{ClusterSpec, ClusterStatus};
 use kube::{
     Resource, ResourceExt,
     api::{ObjectMeta, TypeMeta},
+    runtime::reflector::ObjectRef,
 };
 use serde::{Deserialize, Serialize};
 
+use crate::api::capi_cluster::{self, CLUSTER_SOURCE_ANNOTATION};
 use crate::api::comparable::ResourceDiff;
 use std::collections::HashSet;
 
@@ -74,3 +76,24 @@ impl ResourceDiff for Cluster {
         !annotations_equal || !labels_equal || !owner_references_equal
     }
 }
+
+/// Resolves the CAPI `Cluster` a Fleet cluster was generated for.
+///
+/// The source annotation is preferred, as Fleet clusters in a shared workspace have no owner references.
+pub(crate) fn source_cluster_ref(
+    fleet: &impl ResourceExt,
+) -> Option<ObjectRef<capi_cluster::Cluster>> {
+    if let Some((namespace, name)) = fleet
+        .annotations()
+        .get(CLUSTER_SOURCE_ANNOTATION)
+        .and_then(|source| source.split_once('/'))
+    {
+        return Some(ObjectRef::new(name).within(namespace));
+    }
+
+    let owner = fleet.owner_references().iter().find(|owner| {
+        owner.kind == capi_cluster::Cluster::kind(&())
+            && owner.api_version == capi_cluster::Cluster::api_version(&())
+    })?;
+    Some(ObjectRef::new(&owner.name).within(&fleet.namespace()?))
+}
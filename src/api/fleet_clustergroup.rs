@@ -3,7 +3,8 @@ use std::collections::{BTreeMap, HashSet};
 use fleet_api_rs::fleet_clustergroup::{
     ClusterGroupSelector, ClusterGroupSpec, ClusterGroupStatus,
 };
-use k8s_openapi::api::core::v1::ObjectReference;
+use jiff::Timestamp;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
 use kube::{
     Resource, ResourceExt as _,
     api::{ObjectMeta, TypeMeta},
@@ -13,11 +14,17 @@ use kube::{
 use serde::{Deserialize, Serialize};
 
 use super::capi_clusterclass::ClusterClass;
+use super::fleet_addon_config::{
+    DNS_SUBDOMAIN_MAX_LENGTH, LabelGroup, short_hash, truncate_with_hash,
+};
 use crate::api::comparable::ResourceDiff;
 
 pub static CLUSTER_CLASS_LABEL: &str = "clusterclass-name.fleet.addons.cluster.x-k8s.io";
 pub static CLUSTER_CLASS_NAMESPACE_LABEL: &str =
     "clusterclass-namespace.fleet.addons.cluster.x-k8s.io";
+pub static CLUSTER_CLASS_VERSION_LABEL: &str = "clusterclass-version.fleet.addons.cluster.x-k8s.io";
+pub static LABEL_GROUP_LABEL: &str = "label-group.fleet.addons.cluster.x-k8s.io";
+pub static GROUP_READY_CONDITION: &str = "FleetClusterGroupReady";
 
 #[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
 #[resource(inherit = fleet_api_rs::fleet_clustergroup::ClusterGroup)]
@@ -70,14 +77,180 @@ impl ClusterGroup {
             .cloned()
     }
 
-    pub(crate) fn cluster_class_ref(&self) -> Option<ObjectReference> {
+    pub(crate) fn cluster_class_ref(&self) -> Option<ObjectRef<ClusterClass>> {
         let name = self.cluster_class_name()?;
         let namespace = self.cluster_class_namespace()?;
-        Some(
-            ObjectRef::<ClusterClass>::new(&name)
-                .within(&namespace)
-                .into(),
-        )
+        Some(ObjectRef::<ClusterClass>::new(&name).within(&namespace))
+    }
+
+    /// Builds a `ClusterGroup` with the given name, selecting clusters in the namespace
+    /// with the label value of the generator.
+    pub(crate) fn for_label_value(
+        generator: &LabelGroup,
+        value: &str,
+        name: String,
+        namespace: &str,
+    ) -> Self {
+        let labels = {
+            let mut labels = BTreeMap::default();
+            labels.insert(LABEL_GROUP_LABEL.to_string(), short_hash(&generator.label));
+            Some(labels)
+        };
+
+        let match_labels = {
+            let mut labels = BTreeMap::default();
+            labels.insert(generator.label.clone(), value.to_string());
+            Some(labels)
+        };
+
+        Self {
+            types: Some(TypeMeta::resource::<ClusterGroup>()),
+            metadata: ObjectMeta {
+                name: Some(name),
+                namespace: Some(namespace.to_string()),
+                labels,
+                ..Default::default()
+            },
+            spec: ClusterGroupSpec {
+                selector: Some(ClusterGroupSelector {
+                    match_labels,
+                    ..Default::default()
+                }),
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Builds a version-aware `ClusterGroup` for the `ClusterClass` and Kubernetes minor version
+    /// from the Fleet cluster labels, if the cluster has them.
+    pub(crate) fn for_class_version(
+        cluster_labels: &BTreeMap<String, String>,
+        namespace: &str,
+    ) -> Option<Self> {
+        let class = cluster_labels.get(CLUSTER_CLASS_LABEL)?;
+        let class_namespace = cluster_labels.get(CLUSTER_CLASS_NAMESPACE_LABEL)?;
+        let version = cluster_labels.get(CLUSTER_CLASS_VERSION_LABEL)?;
+
+        let labels = {
+            let mut labels = BTreeMap::default();
+            labels.insert(
+                LABEL_GROUP_LABEL.to_string(),
+                short_hash(CLUSTER_CLASS_VERSION_LABEL),
+            );
+            labels.insert(CLUSTER_CLASS_VERSION_LABEL.to_string(), version.clone());
+            Some(labels)
+        };
+
+        let match_labels = {
+            let mut labels = BTreeMap::default();
+            labels.insert(CLUSTER_CLASS_LABEL.to_string(), class.clone());
+            labels.insert(
+                CLUSTER_CLASS_NAMESPACE_LABEL.to_string(),
+                class_namespace.clone(),
+            );
+            labels.insert(CLUSTER_CLASS_VERSION_LABEL.to_string(), version.clone());
+            Some(labels)
+        };
+
+        Some(Self {
+            types: Some(TypeMeta::resource::<ClusterGroup>()),
+            metadata: ObjectMeta {
+                name: Some(truncate_with_hash(
+                    &format!("{class}.{class_namespace}.{version}"),
+                    DNS_SUBDOMAIN_MAX_LENGTH,
+                )),
+                namespace: Some(namespace.to_string()),
+                labels,
+                ..Default::default()
+            },
+            spec: ClusterGroupSpec {
+                selector: Some(ClusterGroupSelector {
+                    match_labels,
+                    ..Default::default()
+                }),
+            },
+            ..Default::default()
+        })
+    }
+
+    /// Summarizes the status of the groups into a single `FleetClusterGroupReady` condition,
+    /// reporting cluster count, non-ready clusters and non-ready bundles.
+    ///
+    /// Groups without a reported status are skipped, the condition is `Unknown` if none is reported.
+    pub(crate) fn summarize_ready<'a>(
+        groups: impl IntoIterator<Item = &'a ClusterGroup>,
+    ) -> Condition {
+        let statuses: Vec<_> = groups
+            .into_iter()
+            .filter_map(|group| group.status.as_ref())
+            .collect();
+        let (status, reason, message) = match statuses.as_slice() {
+            [] => (
+                "Unknown",
+                "StatusUnavailable",
+                "ClusterGroup status is not yet reported by Fleet".to_string(),
+            ),
+            statuses => {
+                let mut clusters = 0;
+                let mut non_ready_clusters = vec![];
+                let mut non_ready_cluster_count = 0;
+                let mut non_ready_bundles: Vec<String> = vec![];
+                for status in statuses {
+                    let names = status.non_ready_clusters.clone().unwrap_or_default();
+                    clusters += status.cluster_count.unwrap_or_default();
+                    non_ready_cluster_count +=
+                        status.non_ready_cluster_count.unwrap_or(names.len() as i64);
+                    non_ready_clusters.extend(names);
+                    for bundle in status
+                        .summary
+                        .iter()
+                        .flat_map(|summary| summary.non_ready_resources.iter().flatten())
+                        .filter_map(|resource| resource.name.clone())
+                    {
+                        if !non_ready_bundles.contains(&bundle) {
+                            non_ready_bundles.push(bundle);
+                        }
+                    }
+                }
+
+                let mut message = format!(
+                    "{}/{clusters} clusters ready, {} bundles not ready",
+                    clusters - non_ready_cluster_count,
+                    non_ready_bundles.len(),
+                );
+                if !non_ready_clusters.is_empty() {
+                    message.push_str(&format!(
+                        "; non-ready clusters: {}",
+                        non_ready_clusters.join(", ")
+                    ));
+                }
+                if !non_ready_bundles.is_empty() {
+                    message.push_str(&format!(
+                        "; non-ready bundles: {}",
+                        non_ready_bundles.join(", ")
+                    ));
+                }
+
+                match (non_ready_cluster_count, non_ready_bundles.len()) {
+                    (0, 0) => ("True", "Ready", message),
+                    (0, _) => ("False", "BundlesNotReady", message),
+                    _ => ("False", "ClustersNotReady", message),
+                }
+            }
+        };
+
+        Condition {
+            last_transition_time: Time(Timestamp::now()),
+            message,
+            observed_generation: None,
+            reason: reason.into(),
+            status: status.into(),
+            type_: GROUP_READY_CONDITION.into(),
+        }
+    }
+
+    pub(crate) fn label_group_selector() -> Selector {
+        Selector::from_iter([Expression::Exists(LABEL_GROUP_LABEL.to_string())])
     }
 
     pub(crate) fn group_selector() -> Selector {
@@ -133,3 +306,85 @@ impl From<&ClusterClass> for ClusterGroup {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fleet_api_rs::fleet_clustergroup::{
+        ClusterGroupStatus, ClusterGroupStatusSummary, ClusterGroupStatusSummaryNonReadyResources,
+    };
+
+    use super::ClusterGroup;
+
+    #[test]
+    fn test_ready_condition() {
+        let mut group = ClusterGroup::default();
+        assert_eq!("Unknown", ClusterGroup::summarize_ready([&group]).status);
+
+        group.status = Some(ClusterGroupStatus {
+            cluster_count: Some(3),
+            ..Default::default()
+        });
+        let condition = ClusterGroup::summarize_ready([&group]);
+        assert_eq!("True", condition.status);
+        assert_eq!("3/3 clusters ready, 0 bundles not ready", condition.message);
+
+        group.status = Some(ClusterGroupStatus {
+            cluster_count: Some(3),
+            non_ready_cluster_count: Some(1),
+            non_ready_clusters: Some(vec!["dev".into()]),
+            summary: Some(ClusterGroupStatusSummary {
+                non_ready_resources: Some(vec![ClusterGroupStatusSummaryNonReadyResources {
+                    name: Some("cni".into()),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        let condition = ClusterGroup::summarize_ready([&group]);
+        assert_eq!("False", condition.status);
+        assert_eq!("ClustersNotReady", condition.reason);
+        assert_eq!(
+            "2/3 clusters ready, 1 bundles not ready; non-ready clusters: dev; non-ready bundles: cni",
+            condition.message
+        );
+    }
+
+    #[test]
+    fn test_summarize_ready() {
+        let ready = ClusterGroup {
+            status: Some(ClusterGroupStatus {
+                cluster_count: Some(2),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let not_ready = ClusterGroup {
+            status: Some(ClusterGroupStatus {
+                cluster_count: Some(1),
+                non_ready_cluster_count: Some(1),
+                non_ready_clusters: Some(vec!["dev".into()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let unreported = ClusterGroup::default();
+
+        assert_eq!(
+            "Unknown",
+            ClusterGroup::summarize_ready([&unreported]).status
+        );
+
+        let condition = ClusterGroup::summarize_ready([&ready, &unreported]);
+        assert_eq!("True", condition.status);
+        assert_eq!("2/2 clusters ready, 0 bundles not ready", condition.message);
+
+        let condition = ClusterGroup::summarize_ready([&ready, &not_ready, &unreported]);
+        assert_eq!("False", condition.status);
+        assert_eq!("ClustersNotReady", condition.reason);
+        assert_eq!(
+            "2/3 clusters ready, 0 bundles not ready; non-ready clusters: dev",
+            condition.message
+        );
+    }
+}
@@ -7,3 +7,4 @@ pub mod fleet_cluster;
 #[cfg(feature = "agent-initiated")]
 pub mod fleet_cluster_registration_token;
 pub mod fleet_clustergroup;
+pub mod preview;
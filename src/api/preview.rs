@@ -0,0 +1,303 @@
+use k8s_openapi::api::core::v1::Namespace;
+use kube::ResourceExt as _;
+use kube::api::TypeMeta;
+use kube::core::{ParseExpressionError, SelectorExt as _};
+use serde::Serialize;
+use thiserror::Error;
+
+use super::bundle_namespace_mapping::{BundleNamespaceMapping, to_bundle_selector};
+use super::capi_cluster::Cluster;
+use super::capi_clusterclass::ClusterClass;
+use super::fleet_addon_config::{ClusterClassConfig, FleetAddonConfig};
+use super::fleet_cluster;
+use super::fleet_clustergroup::ClusterGroup;
+
+pub type PreviewResult<T, E = PreviewError> = std::result::Result<T, E>;
+
+#[derive(Error, Debug)]
+pub enum PreviewError {
+    #[error("ClusterClass bundle selector parse error: {0}")]
+    BundleSelector(#[from] serde_json::Error),
+
+    #[error("Cluster import selector parse error: {0}")]
+    Selector(#[from] ParseExpressionError),
+
+    #[error("Preview encoding error: {0}")]
+    Encode(#[from] serde_yaml::Error),
+}
+
+/// Fleet objects generated by the controllers for a CAPI `Cluster` and its `ClusterClass`.
+///
+/// Rendered without a cluster connection, so the workspace mapping and the objects
+/// depending on the live cluster state are not included.
+#[derive(Debug, Default)]
+pub struct Preview {
+    pub namespace: Option<Namespace>,
+    pub cluster: Option<fleet_cluster::Cluster>,
+    pub cluster_group: Option<ClusterGroup>,
+    pub bundle_namespace_mapping: Option<BundleNamespaceMapping>,
+    pub class_group: Option<ClusterGroup>,
+}
+
+impl Preview {
+    /// Renders the objects the controllers would create for the cluster and the class.
+    ///
+    /// Cluster objects are only rendered if the cluster is imported, matching the `selector`,
+    /// or residing in a namespace matching the `namespaceSelector` of the cluster config.
+    /// Without the cluster `Namespace`, its labels are assumed to be empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cluster selectors or the `ClusterClass` bundle selector
+    /// annotation are not valid.
+    pub fn render(
+        cluster: &Cluster,
+        namespace: Option<&Namespace>,
+        class: Option<&ClusterClass>,
+        config: &FleetAddonConfig,
+    ) -> PreviewResult<Self> {
+        let mut preview = Preview::default();
+
+        if config.cluster_operations_enabled() && selected(cluster, namespace, config)? {
+            let cluster_config = config.spec.cluster.as_ref();
+            let mut fleet = cluster.to_cluster(cluster_config);
+            fleet.types = Some(TypeMeta::resource::<fleet_cluster::Cluster>());
+            if config.cluster_class_version_groups_enabled() {
+                fleet
+                    .metadata
+                    .labels
+                    .get_or_insert_default()
+                    .extend(cluster.class_version_labels());
+            }
+
+            let mut mapping = cluster.to_bundle_ns_mapping(cluster_config);
+            if let (Some(mapping), Some(class)) = (mapping.as_mut(), class)
+                && let Some(selector) = class.bundle_selector()?
+            {
+                mapping.bundle_selector = to_bundle_selector(&selector);
+            }
+
+            preview.namespace = Some(cluster.to_namespace());
+            preview.cluster = Some(fleet);
+            preview.cluster_group = cluster.to_group(cluster_config);
+            preview.bundle_namespace_mapping = mapping;
+        }
+
+        if let Some(class) = class
+            && config.cluster_class_operations_enabled()
+        {
+            let mut group: ClusterGroup = class.into();
+            if !matches!(
+                config.spec.cluster_class,
+                Some(ClusterClassConfig {
+                    set_owner_references: Some(true),
+                    ..
+                })
+            ) {
+                group.metadata.owner_references = None;
+            }
+            preview.class_group = Some(group);
+        }
+
+        Ok(preview)
+    }
+
+    /// Encodes the rendered objects as a multi-document YAML.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an object can't be encoded.
+    pub fn to_yaml(&self) -> PreviewResult<String> {
+        let mut documents = vec![];
+        push_document(&mut documents, self.namespace.as_ref())?;
+        push_document(&mut documents, self.cluster.as_ref())?;
+        push_document(&mut documents, self.cluster_group.as_ref())?;
+        push_document(&mut documents, self.bundle_namespace_mapping.as_ref())?;
+        push_document(&mut documents, self.class_group.as_ref())?;
+
+        Ok(documents.join("---\n"))
+    }
+}
+
+/// Checks if the cluster is imported, same as the controller dynamic watches do.
+fn selected(
+    cluster: &Cluster,
+    namespace: Option<&Namespace>,
+    config: &FleetAddonConfig,
+) -> PreviewResult<bool> {
+    let namespace_labels = namespace.map(|ns| ns.labels().clone()).unwrap_or_default();
+    Ok(config.cluster_selector()?.matches(cluster.labels())
+        || config.namespace_selector()?.matches(&namespace_labels))
+}
+
+fn push_document(documents: &mut Vec<String>, obj: Option<&impl Serialize>) -> PreviewResult<()> {
+    if let Some(obj) = obj {
+        documents.push(serde_yaml::to_string(obj)?);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::api::core::v1::Namespace;
+
+    use crate::api::capi_cluster::Cluster;
+    use crate::api::capi_clusterclass::ClusterClass;
+    use crate::api::fleet_addon_config::FleetAddonConfig;
+
+    use super::Preview;
+
+    #[test]
+    fn test_render() {
+        let cluster: Cluster = serde_yaml::from_str(
+            r"
+            apiVersion: cluster.x-k8s.io/v1beta2
+            kind: Cluster
+            metadata:
+              name: dev
+              namespace: clusters
+              labels:
+                env: dev
+            spec:
+              topology:
+                version: v1.33.1
+                classRef:
+                  name: quick-start
+                  namespace: classes
+            ",
+        )
+        .unwrap();
+        let class: ClusterClass = serde_yaml::from_str(
+            r#"
+            apiVersion: cluster.x-k8s.io/v1beta2
+            kind: ClusterClass
+            metadata:
+              name: quick-start
+              namespace: classes
+              annotations:
+                bundle-selector.fleet.addons.cluster.x-k8s.io: '{"matchLabels":{"app":"cni"}}'
+            spec:
+              controlPlane:
+                templateRef:
+                  apiVersion: controlplane.cluster.x-k8s.io/v1beta2
+                  kind: KubeadmControlPlaneTemplate
+                  name: quick-start-control-plane
+              infrastructure:
+                templateRef:
+                  apiVersion: infrastructure.cluster.x-k8s.io/v1beta2
+                  kind: DockerClusterTemplate
+                  name: quick-start-cluster
+            "#,
+        )
+        .unwrap();
+        let config: FleetAddonConfig = serde_yaml::from_str(
+            r"
+            apiVersion: addons.cluster.x-k8s.io/v1alpha1
+            kind: FleetAddonConfig
+            metadata:
+              name: fleet-addon-config
+            spec:
+              cluster:
+                applyClassGroup: true
+                namespaceSelector: {}
+                selector: {}
+              clusterClass: {}
+            ",
+        )
+        .unwrap();
+
+        let preview = Preview::render(&cluster, None, Some(&class), &config).unwrap();
+        let fleet = preview.cluster.unwrap();
+        assert_eq!(Some("dev"), fleet.metadata.name.as_deref());
+        assert_eq!(
+            Some(&"dev".to_string()),
+            fleet.metadata.labels.unwrap().get("env")
+        );
+        assert!(preview.namespace.is_some());
+        assert!(preview.cluster_group.is_some());
+        assert!(preview.class_group.is_some());
+        let mapping = preview.bundle_namespace_mapping.unwrap();
+        assert_eq!(Some("classes"), mapping.metadata.namespace.as_deref());
+        assert_eq!(
+            Some(&"cni".to_string()),
+            mapping
+                .bundle_selector
+                .match_labels
+                .unwrap_or_default()
+                .get("app")
+        );
+
+        // Disabled operations render nothing
+        let mut config = config;
+        config.spec.cluster = None;
+        config.spec.cluster_class = None;
+        let yaml = Preview::render(&cluster, None, Some(&class), &config)
+            .unwrap()
+            .to_yaml()
+            .unwrap();
+        assert!(yaml.is_empty());
+    }
+
+    #[test]
+    fn test_render_selectors() {
+        let cluster: Cluster = serde_yaml::from_str(
+            r"
+            apiVersion: cluster.x-k8s.io/v1beta2
+            kind: Cluster
+            metadata:
+              name: dev
+              namespace: clusters
+              labels:
+                env: dev
+            spec: {}
+            ",
+        )
+        .unwrap();
+        let namespace: Namespace = serde_yaml::from_str(
+            r"
+            apiVersion: v1
+            kind: Namespace
+            metadata:
+              name: clusters
+              labels:
+                import: 'true'
+            ",
+        )
+        .unwrap();
+        let config = |selectors: &str| -> FleetAddonConfig {
+            serde_yaml::from_str(&format!(
+                r"
+                apiVersion: addons.cluster.x-k8s.io/v1alpha1
+                kind: FleetAddonConfig
+                metadata:
+                  name: fleet-addon-config
+                spec:
+                  cluster:
+                    {selectors}
+                "
+            ))
+            .unwrap()
+        };
+
+        let unmatched = "{namespaceSelector: {matchLabels: {import: 'true'}}, selector: {matchLabels: {env: prod}}}";
+        let preview = Preview::render(&cluster, None, None, &config(unmatched)).unwrap();
+        assert!(preview.cluster.is_none());
+        assert!(preview.to_yaml().unwrap().is_empty());
+
+        // Matching namespace labels import the cluster
+        let preview =
+            Preview::render(&cluster, Some(&namespace), None, &config(unmatched)).unwrap();
+        assert!(preview.cluster.is_some());
+        assert!(preview.namespace.is_some());
+
+        // Matching cluster labels import the cluster
+        let cluster_matched = "{namespaceSelector: {matchLabels: {import: 'false'}}, selector: {matchLabels: {env: dev}}}";
+        let preview = Preview::render(&cluster, None, None, &config(cluster_matched)).unwrap();
+        assert!(preview.cluster.is_some());
+
+        let invalid =
+            "{namespaceSelector: {}, selector: {matchExpressions: [{key: env, operator: Bogus}]}}";
+        assert!(Preview::render(&cluster, None, None, &config(invalid)).is_err());
+    }
+}
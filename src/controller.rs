@@ -1,24 +1,33 @@
 use crate::api::bundle_namespace_mapping::BundleNamespaceMapping;
-use crate::api::capi_cluster::Cluster;
+use crate::api::capi_cluster::{CLUSTER_NAME_LABEL, Cluster};
 use crate::api::capi_clusterclass::ClusterClass;
-use crate::api::fleet_addon_config::FleetAddonConfig;
-use crate::api::fleet_cluster;
-use crate::api::fleet_clustergroup::ClusterGroup;
-use crate::controllers::addon_config::FleetConfig;
+use crate::api::fleet_addon_config::{FleetAddonConfig, FleetAddonConfigStatus};
+use crate::api::fleet_cluster::{self, source_cluster_ref};
+use crate::api::fleet_clustergroup::{
+    CLUSTER_CLASS_LABEL, CLUSTER_CLASS_NAMESPACE_LABEL, ClusterGroup,
+};
+use crate::controllers::addon_config::{
+    FLEET_UNINSTALL_FINALIZER, FleetConfig, SERVER_CA_VALID_CONDITION,
+};
+use crate::controllers::conditions;
 use crate::controllers::controller::{Context, DynamicStream, FleetController, fetch_config};
+use crate::controllers::dry_run::{DryRun, DryRunOperation, DryRunReport};
+use crate::controllers::import_progress::{self, ImportProgress};
+use crate::controllers::stores::{ClusterIndex, Stores};
 use crate::metrics::Diagnostics;
 use crate::multi_dispatcher::{BroadcastStream, MultiDispatcher, broadcaster};
 use crate::{Error, Metrics};
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use futures::stream::BoxStream;
 use futures::{Stream, StreamExt};
 
-use jiff::Timestamp;
-use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
-use kube::api::{Patch, PatchParams};
+use k8s_openapi::NamespaceResourceScope;
+use k8s_openapi::api::core::v1::Secret;
+use kube::api::{PartialObjectMeta, Patch, PatchParams};
 use kube::core::DeserializeGuard;
-use kube::runtime::reflector::ObjectRef;
 use kube::runtime::reflector::store::Writer;
+use kube::runtime::reflector::{ObjectRef, Store};
 use kube::runtime::{WatchStreamExt, metadata_watcher, predicates, reflector, watcher};
 use kube::{Resource, ResourceExt};
 use kube::{
@@ -29,10 +38,13 @@ use kube::{
         watcher::Config,
     },
 };
+use semver::Version;
 use tokio::sync::Barrier;
 
-use std::collections::BTreeMap;
-
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
 use std::ops::Deref;
 use std::sync::Arc;
 use tokio::{sync::RwLock, time::Duration};
@@ -43,6 +55,10 @@ use tracing::{self, warn};
 pub struct State {
     /// Diagnostics populated by the reconciler
     diagnostics: Arc<RwLock<Diagnostics>>,
+    /// Import progress populated by the cluster reconciler
+    imports: ImportProgress,
+    /// Changes computed in the dry-run mode
+    dry_run: DryRun,
     /// Metrics registry
     registry: prometheus::Registry,
     metrics: Metrics,
@@ -57,9 +73,14 @@ pub struct State {
 
     // k8s api server minor version
     pub version: u32,
+    // k8s api server version
+    pub kube_version: Version,
 
     // Controller readiness barrier
     pub barrier: Arc<Barrier>,
+
+    // Reflector stores available to the reconcilers
+    stores: Stores,
 }
 
 #[derive(Parser, Debug, Clone, Default)]
@@ -67,6 +88,85 @@ pub struct Flags {
     /// helm install allows to select container for performing fleet chart installation
     #[arg(long)]
     pub helm_install: bool,
+
+    /// Where helm install, upgrade and uninstall operations are executed
+    #[arg(long, value_enum, default_value_t = HelmInstaller::Process)]
+    pub helm_installer: HelmInstaller,
+
+    /// Namespace for the helm operation Jobs
+    #[arg(long, default_value = "caapf-system")]
+    pub helm_job_namespace: String,
+
+    /// `ServiceAccount` used by the helm operation Jobs
+    #[arg(long, default_value = "caapf-helm-manager")]
+    pub helm_job_service_account: String,
+
+    /// Container image with the helm binary used by the helm operation Jobs
+    #[arg(long, default_value = "registry.suse.com/suse/helm:3.17")]
+    pub helm_job_image: String,
+
+    /// Compute and log the changes to the generated objects instead of writing them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Address of the web server, serving health, metrics and dry-run changes
+    #[arg(long, default_value = "0.0.0.0:8443")]
+    pub bind_address: String,
+
+    /// Namespaces watched by the controllers, comma separated. All namespaces are watched when empty
+    #[arg(long, value_delimiter = ',')]
+    pub watch_namespaces: Vec<String>,
+
+    /// Namespace of the fleet installation
+    #[arg(long, default_value = "cattle-fleet-system")]
+    pub fleet_namespace: String,
+
+    /// Name of the fleet controller `ConfigMap` in the fleet namespace
+    #[arg(long, default_value = "fleet-controller")]
+    pub fleet_config_name: String,
+
+    /// Namespace of the `kube-root-ca.crt` `ConfigMap` used as the inferred API server CA
+    #[arg(long, default_value = "default")]
+    pub ca_namespace: String,
+}
+
+impl Flags {
+    /// Namespaces watched by the controllers, `None` standing for all namespaces.
+    #[must_use]
+    pub fn watched_namespaces(&self) -> Vec<Option<&str>> {
+        if self.watch_namespaces.is_empty() {
+            return vec![None];
+        }
+
+        self.watch_namespaces
+            .iter()
+            .map(|ns| Some(ns.as_str()))
+            .collect()
+    }
+
+    /// API of every watched namespace.
+    pub(crate) fn watched_apis<K>(&self, client: &Client) -> Vec<Api<K>>
+    where
+        K: Resource<Scope = NamespaceResourceScope, DynamicType = ()>,
+    {
+        self.watched_namespaces()
+            .into_iter()
+            .map(|ns| match ns {
+                Some(ns) => Api::namespaced(client.clone(), ns),
+                None => Api::all(client.clone()),
+            })
+            .collect()
+    }
+}
+
+/// Executor for the mutating helm operations.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HelmInstaller {
+    /// Run helm in the controller container
+    #[default]
+    Process,
+    /// Run helm in Kubernetes Jobs
+    Job,
 }
 
 impl State {
@@ -74,17 +174,23 @@ impl State {
     ///
     /// Panics if the default metrics cannot be registered with the registry.
     #[must_use]
-    pub fn new(version: u32) -> Self {
+    pub fn new(kube_version: Version) -> Self {
         let registry = prometheus::Registry::default();
         Self {
             metrics: Metrics::default().register(&registry).unwrap(),
+            diagnostics: Arc::new(RwLock::new(
+                Diagnostics::default().register(&registry).unwrap(),
+            )),
             registry,
             flags: Flags::parse(),
             dispatcher: MultiDispatcher::new(128),
-            diagnostics: Arc::default(),
+            imports: ImportProgress::default(),
+            dry_run: DryRun::default(),
             stream: BroadcastStream::new(Arc::default()),
-            version,
+            version: kube_version.minor as u32,
+            kube_version,
             barrier: Arc::new(Barrier::new(3)),
+            stores: Stores::default(),
         }
     }
 
@@ -99,6 +205,11 @@ impl State {
         self.diagnostics.read().await.clone()
     }
 
+    /// Dry-run diffs and helm operations getter
+    pub async fn dry_run(&self) -> DryRunReport {
+        self.dry_run.report().await
+    }
+
     // Create a Controller Context that can update State
     #[must_use]
     pub fn to_context(&self, client: Client) -> Arc<Context> {
@@ -109,7 +220,12 @@ impl State {
             dispatcher: self.dispatcher.clone(),
             stream: self.stream.clone(),
             version: self.version,
+            kube_version: self.kube_version.clone(),
             barrier: self.barrier.clone(),
+            stores: self.stores.clone(),
+            flags: self.flags.clone(),
+            imports: self.imports.clone(),
+            dry_run: self.dry_run.clone(),
         })
     }
 }
@@ -144,21 +260,210 @@ trait ControllerDefault: WatchStreamExt {
 
 impl<St: ?Sized> ControllerDefault for St where St: Stream {}
 
+type WatchStream<K> = BoxStream<'static, Result<watcher::Event<K>, watcher::Error>>;
+
+/// Merges the watcher streams of the watched namespaces into a single stream.
+///
+/// The initial lists of the streams can't share a store, so the merged stream is initialized
+/// once all streams are listed. Later relists are applied as updates, deleting the objects
+/// missing from the new list.
+fn select_watchers<K>(mut streams: Vec<WatchStream<K>>) -> WatchStream<K>
+where
+    K: Resource<DynamicType = ()> + Clone + Send + 'static,
+{
+    if streams.len() == 1
+        && let Some(stream) = streams.pop()
+    {
+        return stream;
+    }
+
+    let mut merged = MergedWatch::new(streams.len());
+    let streams = streams
+        .into_iter()
+        .enumerate()
+        .map(|(index, stream)| stream.map(move |event| (index, event)).boxed());
+    futures::stream::select_all(streams)
+        .flat_map(move |(index, event)| {
+            futures::stream::iter(match event {
+                Ok(event) => merged.apply(index, event).into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            })
+        })
+        .boxed()
+}
+
+/// Objects listed by each of the merged watcher streams.
+struct MergedWatch<K: Resource> {
+    objects: Vec<HashMap<ObjectRef<K>, K>>,
+    /// Objects of the ongoing list of each stream
+    relists: Vec<Option<HashMap<ObjectRef<K>, K>>>,
+    /// Streams yet to complete the initial list
+    pending: HashSet<usize>,
+}
+
+impl<K: Resource<DynamicType = ()> + Clone> MergedWatch<K> {
+    fn new(streams: usize) -> Self {
+        Self {
+            objects: (0..streams).map(|_| HashMap::new()).collect(),
+            relists: (0..streams).map(|_| None).collect(),
+            pending: (0..streams).collect(),
+        }
+    }
+
+    /// Applies the event of the stream, returning the events of the merged stream.
+    fn apply(&mut self, stream: usize, event: watcher::Event<K>) -> Vec<watcher::Event<K>> {
+        let initialized = self.pending.is_empty();
+        let mut events = vec![];
+        match event {
+            watcher::Event::Init => self.relists[stream] = Some(HashMap::new()),
+            watcher::Event::InitApply(obj) => {
+                self.relists[stream]
+                    .get_or_insert_default()
+                    .insert(ObjectRef::from_obj(&obj), obj.clone());
+                events.push(watcher::Event::Apply(obj));
+            }
+            watcher::Event::InitDone => {
+                let listed = self.relists[stream].take().unwrap_or_default();
+                let previous = std::mem::replace(&mut self.objects[stream], listed);
+                events.extend(
+                    previous
+                        .into_iter()
+                        .filter(|(key, _)| !self.objects[stream].contains_key(key))
+                        .map(|(_, obj)| watcher::Event::Delete(obj)),
+                );
+                self.pending.remove(&stream);
+            }
+            watcher::Event::Apply(obj) => {
+                self.objects[stream].insert(ObjectRef::from_obj(&obj), obj.clone());
+                events.push(watcher::Event::Apply(obj));
+            }
+            watcher::Event::Delete(obj) => {
+                self.objects[stream].remove(&ObjectRef::from_obj(&obj));
+                events.push(watcher::Event::Delete(obj));
+            }
+        }
+
+        match (initialized, self.pending.is_empty()) {
+            (true, _) => events,
+            (false, false) => vec![],
+            // The last initial list is done, the merged stream is listed at once
+            (false, true) => std::iter::once(watcher::Event::Init)
+                .chain(
+                    self.objects
+                        .iter()
+                        .flat_map(HashMap::values)
+                        .cloned()
+                        .map(watcher::Event::InitApply),
+                )
+                .chain(std::iter::once(watcher::Event::InitDone))
+                .collect(),
+        }
+    }
+}
+
+/// Objects with changed labels, including the created and the deleted objects.
+///
+/// Status updates, such as the Fleet cluster heartbeats, are filtered out.
+fn label_changes<K, S>(stream: S) -> impl Stream<Item = Result<K, watcher::Error>>
+where
+    K: Resource<DynamicType = ()>,
+    S: Stream<Item = Result<watcher::Event<K>, watcher::Error>>,
+{
+    let mut observed = HashMap::new();
+    stream.filter_map(move |event| {
+        let changed = match event {
+            Ok(watcher::Event::Apply(obj) | watcher::Event::InitApply(obj)) => {
+                let labels = predicates::labels(&obj);
+                (observed.insert(ObjectRef::from_obj(&obj), labels) != Some(labels))
+                    .then_some(Ok(obj))
+            }
+            Ok(watcher::Event::Delete(obj)) => {
+                observed.remove(&ObjectRef::from_obj(&obj));
+                Some(Ok(obj))
+            }
+            Ok(watcher::Event::Init | watcher::Event::InitDone) => None,
+            Err(e) => Some(Err(e)),
+        };
+        futures::future::ready(changed)
+    })
+}
+
+/// Watches the objects in the watched namespaces.
+fn scoped_watcher<K>(flags: &Flags, client: &Client, config: &Config) -> WatchStream<K>
+where
+    K: Resource<Scope = NamespaceResourceScope, DynamicType = ()>,
+    K: Clone + DeserializeOwned + Debug + Send + 'static,
+{
+    select_watchers(
+        flags
+            .watched_apis(client)
+            .into_iter()
+            .map(|api| watcher(api, config.clone()).boxed())
+            .collect(),
+    )
+}
+
+/// Watches the object metadata in the watched namespaces.
+fn scoped_metadata_watcher<K>(
+    flags: &Flags,
+    client: &Client,
+    config: &Config,
+) -> WatchStream<PartialObjectMeta<K>>
+where
+    K: Resource<Scope = NamespaceResourceScope, DynamicType = ()>,
+    K: Clone + DeserializeOwned + Debug + Send + 'static,
+{
+    select_watchers(
+        flags
+            .watched_apis(client)
+            .into_iter()
+            .map(|api| metadata_watcher(api, config.clone()).boxed())
+            .collect(),
+    )
+}
+
 /// # Panics
 ///
 /// Panics if the kube Client cannot be created or if the watcher stream panics unexpectedly.
-pub async fn run_fleet_addon_config_controller(state: State) {
+pub async fn run_fleet_addon_config_controller(mut state: State) {
     let client = Client::try_default()
         .await
         .expect("failed to create kube Client");
 
+    let (fleet_reader, fleet_writer) = reflector::store();
+    state.stores.fleet_clusters = Some(fleet_reader);
+    let imported_clusters = label_changes(
+        scoped_metadata_watcher::<fleet_cluster::Cluster>(
+            &state.flags,
+            &client,
+            &Config::default().any_semantic(),
+        )
+        .modify(|c| c.managed_fields_mut().clear())
+        .reflect(fleet_writer),
+    )
+    .default_backoff();
+
+    let (group_reader, group_writer) = reflector::store();
+    state.stores.label_groups = Some(group_reader);
+    let label_groups = scoped_watcher::<ClusterGroup>(
+        &state.flags,
+        &client,
+        &Config::default()
+            .labels_from(&ClusterGroup::label_group_selector())
+            .any_semantic(),
+    )
+    .default_with_reflect(group_writer);
+
     let config_controller = Controller::new(
         Api::<FleetAddonConfig>::all(client.clone()),
         Config::default().any_semantic(),
     )
     .watches(
-        Api::<DeserializeGuard<FleetConfig>>::all(client.clone()),
-        Config::default().fields("metadata.name=fleet-controller"),
+        Api::<DeserializeGuard<FleetConfig>>::namespaced(
+            client.clone(),
+            &state.flags.fleet_namespace,
+        ),
+        Config::default().fields(&format!("metadata.name={}", state.flags.fleet_config_name)),
         |config| config.0.ok().map(|_| ObjectRef::new("fleet-addon-config")),
     )
     .shutdown_on_signal()
@@ -183,6 +488,23 @@ pub async fn run_fleet_addon_config_controller(state: State) {
     .default_backoff()
     .for_each(|_| futures::future::ready(()));
 
+    let label_groups_controller = Controller::new(
+        Api::<FleetAddonConfig>::all(client.clone()),
+        Config::default().any_semantic(),
+    )
+    .watches_stream(imported_clusters, |_| {
+        Some(ObjectRef::new("fleet-addon-config"))
+    })
+    .watches_stream(label_groups, |_| Some(ObjectRef::new("fleet-addon-config")))
+    .shutdown_on_signal()
+    .run(
+        FleetAddonConfig::reconcile_label_groups,
+        error_policy,
+        state.to_context(client.clone()),
+    )
+    .default_backoff()
+    .for_each(|_| futures::future::ready(()));
+
     let watcher = broadcaster(state.dispatcher.clone(), state.stream.clone())
         .for_each(|_| futures::future::ready(()));
 
@@ -201,7 +523,7 @@ pub async fn run_fleet_addon_config_controller(state: State) {
 
     tokio::select! {
         () = watcher => {panic!("This should not happen before controllers exit")},
-        _ = futures::future::join(dynamic_watches_controller, config_controller) => {}
+        _ = futures::future::join3(dynamic_watches_controller, config_controller, label_groups_controller) => {}
     };
 }
 
@@ -218,55 +540,62 @@ pub async fn run_fleet_helm_controller(state: State) {
         Config::default().any_semantic(),
     )
     .default_with_reflect(writer)
-    .predicate_filter(predicates::generation, Default::default());
+    .predicate_filter(
+        crate::predicates::generation_with_deletion,
+        Default::default(),
+    );
 
     let fleet_addon_config_controller = Controller::for_stream(fleet_addon_config, reader)
         .shutdown_on_signal()
         .run(
-            |obj, ctx| async move {
-                let mut obj = obj.deref().clone();
+            |live, ctx| async move {
+                let mut obj = live.deref().clone();
                 obj.metadata.managed_fields = None;
                 let res = FleetAddonConfig::reconcile_helm(&mut obj, ctx.clone()).await;
-                let status = obj.status.get_or_insert_default();
-                let conditions = &mut status.conditions;
-                let mut message = "Addon provider is ready".to_string();
-                let mut status_message = "True";
-                if let Err(ref e) = res {
-                    message = format!("FleetAddonConfig reconcile error: {e}");
-                    status_message = "False";
+                // The config is removed once the uninstall finalizer is released
+                if obj.metadata.deletion_timestamp.is_some()
+                    && !obj
+                        .finalizers()
+                        .iter()
+                        .any(|f| f == FLEET_UNINSTALL_FINALIZER)
+                {
+                    return res;
                 }
-                conditions.push(Condition {
-                    last_transition_time: Time(Timestamp::now()),
-                    message,
-                    observed_generation: obj.metadata.generation,
-                    reason: "Ready".into(),
-                    status: status_message.into(),
-                    type_: "Ready".into(),
-                });
 
+                let generation = obj.metadata.generation;
                 let status = obj.status.get_or_insert_default();
-                let mut uniques: BTreeMap<String, Condition> = BTreeMap::new();
-                status
-                    .conditions
-                    .iter()
-                    .for_each(|e| match uniques.get(&e.type_) {
-                        Some(existing)
-                            if existing.message == e.message
-                                && existing.reason == e.reason
-                                && existing.status == e.status
-                                && existing.observed_generation == e.observed_generation => {}
-                        _ => {
-                            uniques.insert(e.type_.clone(), e.clone());
-                        }
-                    });
-                status.conditions = uniques.into_values().collect();
+                status.observed_generation = generation;
+                conditions::summarize(&mut status.conditions, generation, res.as_ref().err());
+
+                // Import progress and the server CA condition are reported by other controllers,
+                // and the stored copy may be stale, so only the fields set by the helm reconcile are applied
+                let patch = json!({
+                    "apiVersion": FleetAddonConfig::api_version(&()),
+                    "kind": FleetAddonConfig::kind(&()),
+                    "status": FleetAddonConfigStatus {
+                        import_progress: None,
+                        conditions: status
+                            .conditions
+                            .iter()
+                            .filter(|c| c.type_ != SERVER_CA_VALID_CONDITION)
+                            .cloned()
+                            .collect(),
+                        ..status.clone()
+                    },
+                });
+                if ctx.flags.dry_run {
+                    ctx.dry_run
+                        .merge(&*live, DryRunOperation::Status, &patch)
+                        .await;
+                    return res;
+                }
 
                 let api: Api<FleetAddonConfig> = Api::all(ctx.client.clone());
                 let patch = api
                     .patch_status(
                         &obj.name_any(),
                         &PatchParams::apply("fleet-addon-controller").force(),
-                        &Patch::Apply(obj),
+                        &Patch::Apply(patch),
                     )
                     .await;
                 match res {
@@ -287,13 +616,16 @@ pub async fn run_fleet_helm_controller(state: State) {
 
 /// Initialize the controller and shared state (given the crd is installed)
 ///
+/// The `ClusterClass` store is maintained by the `ClusterClass` controller.
+///
 /// # Panics
 ///
 /// Panics if the kube Client cannot be created.
-pub async fn run_cluster_controller(state: State) {
+pub async fn run_cluster_controller(mut state: State, cluster_classes: Store<ClusterClass>) {
     let client = Client::try_default()
         .await
         .expect("failed to create kube Client");
+    state.stores.cluster_classes = Some(cluster_classes);
 
     let (sub, reader) = state.dispatcher.subscribe();
     let ns_controller = Controller::for_shared_stream(sub, reader)
@@ -306,43 +638,69 @@ pub async fn run_cluster_controller(state: State) {
         .default_backoff()
         .for_each(|_| futures::future::ready(()));
 
-    let fleet = metadata_watcher(
-        Api::<fleet_cluster::Cluster>::all(client.clone()),
-        Config::default().any_semantic(),
+    let (fleet_reader, fleet_writer) = reflector::store();
+    state.stores.fleet_clusters = Some(fleet_reader);
+    let fleet = scoped_metadata_watcher::<fleet_cluster::Cluster>(
+        &state.flags,
+        &client,
+        &Config::default().any_semantic(),
     )
-    .default_handling();
+    .default_with_reflect(fleet_writer);
 
-    let groups = metadata_watcher(
-        Api::<ClusterGroup>::all(client.clone()),
-        Config::default()
+    let groups = scoped_metadata_watcher::<ClusterGroup>(
+        &state.flags,
+        &client,
+        &Config::default()
             .labels_from(&ClusterGroup::group_selector())
             .any_semantic(),
     )
     .default_handling();
 
-    let mappings = metadata_watcher(
-        Api::<BundleNamespaceMapping>::all(client.clone()),
-        Config::default().any_semantic(),
+    let mappings = scoped_metadata_watcher::<BundleNamespaceMapping>(
+        &state.flags,
+        &client,
+        &Config::default().any_semantic(),
+    )
+    .default_handling();
+
+    let kubeconfigs = scoped_metadata_watcher::<Secret>(
+        &state.flags,
+        &client,
+        &Config::default().labels(CLUSTER_NAME_LABEL).any_semantic(),
     )
     .default_handling();
 
     let (sub, reader) = state.dispatcher.subscribe();
-    let clusters = Controller::for_shared_stream(sub, reader.clone())
-        .owns_stream(fleet)
+    let index = ClusterIndex::new(reader.clone());
+    state.stores.clusters = Some(index.clone());
+    let sub = sub.inspect({
+        let index = index.clone();
+        move |cluster| index.index(cluster)
+    });
+
+    let import_progress = import_progress::report(state.to_context(client.clone()), reader.clone());
+    let clusters = Controller::for_shared_stream(sub, reader)
+        .watches_stream(fleet, |fleet| source_cluster_ref(&fleet))
         .owns_stream(groups)
+        .watches_stream(kubeconfigs, |secret| {
+            let cluster = secret.labels().get(CLUSTER_NAME_LABEL)?;
+            let namespace = secret.namespace()?;
+            (secret.name_any() == format!("{cluster}-kubeconfig"))
+                .then(|| ObjectRef::new(cluster).within(&namespace))
+        })
         .watches_stream(mappings, move |mapping| {
-            reader
-                .state()
+            index
+                .by_class_namespace(&mapping.namespace().unwrap_or_default())
                 .into_iter()
-                .filter_map(move |c: Arc<Cluster>| {
-                    let in_namespace =
-                        c.spec.proxy.topology.as_ref()?.class_ref.namespace == mapping.namespace();
-                    in_namespace.then_some(ObjectRef::from_obj(&*c))
-                })
+                .map(|c| ObjectRef::from_obj(&*c))
         })
         .shutdown_on_signal()
         .run(
-            Cluster::reconcile,
+            |cluster, ctx| async move {
+                let res = cluster.clone().reconcile(ctx.clone()).await;
+                ctx.imports.observe(&cluster, &res);
+                res
+            },
             error_policy,
             state.to_context(client.clone()),
         )
@@ -352,51 +710,69 @@ pub async fn run_cluster_controller(state: State) {
     // Signal that this controller is ready
     state.barrier.wait().await;
 
-    tokio::join!(clusters, ns_controller);
+    tokio::select! {
+        _ = futures::future::join(clusters, ns_controller) => {},
+        () = import_progress => {},
+    };
 }
 
 /// Initialize the controller and shared state (given the crd is installed)
 ///
+/// The `ClusterClass` store is filled from the writer, and shared with the cluster controller.
+///
 /// # Panics
 ///
 /// Panics if the kube Client cannot be created.
-pub async fn run_cluster_class_controller(state: State) {
+pub async fn run_cluster_class_controller(mut state: State, writer: Writer<ClusterClass>) {
     let client = Client::try_default()
         .await
         .expect("failed to create kube Client");
 
-    let group_controller = Controller::new(
-        Api::<ClusterGroup>::all(client.clone()),
-        Config::default()
+    let reader = writer.as_reader();
+    state.stores.cluster_classes = Some(reader.clone());
+
+    let (group_reader, group_writer) = reflector::store();
+    state.stores.class_groups = Some(group_reader.clone());
+    let class_groups = scoped_watcher::<ClusterGroup>(
+        &state.flags,
+        &client,
+        &Config::default()
             .labels_from(&ClusterGroup::group_selector())
             .any_semantic(),
     )
-    .shutdown_on_signal()
-    .run(
-        ClusterGroup::reconcile,
-        error_policy,
-        state.to_context(client.clone()),
-    )
-    .default_backoff()
-    .for_each(|_| futures::future::ready(()));
+    .default_with_reflect(group_writer);
 
-    let (reader, writer) = reflector::store();
-    let cluster_classes = watcher(
-        Api::<ClusterClass>::all(client.clone()),
-        Config::default().any_semantic(),
-    )
-    .default_with_reflect(writer);
+    let group_controller = Controller::for_stream(class_groups, group_reader)
+        .shutdown_on_signal()
+        .run(
+            ClusterGroup::reconcile,
+            error_policy,
+            state.to_context(client.clone()),
+        )
+        .default_backoff()
+        .for_each(|_| futures::future::ready(()));
 
-    let groups = metadata_watcher(
-        Api::<ClusterGroup>::all(client.clone()),
-        Config::default()
+    let cluster_classes =
+        scoped_watcher::<ClusterClass>(&state.flags, &client, &Config::default().any_semantic())
+            .default_with_reflect(writer);
+
+    let groups = scoped_metadata_watcher::<ClusterGroup>(
+        &state.flags,
+        &client,
+        &Config::default()
             .labels_from(&ClusterGroup::group_selector())
             .any_semantic(),
     )
     .default_handling();
 
     let cluster_class_controller = Controller::for_stream(cluster_classes, reader)
-        .owns_stream(groups)
+        .watches_stream(groups, |group| {
+            let labels = group.labels();
+            Some(
+                ObjectRef::new(labels.get(CLUSTER_CLASS_LABEL)?)
+                    .within(labels.get(CLUSTER_CLASS_NAMESPACE_LABEL)?),
+            )
+        })
         .shutdown_on_signal()
         .run(
             ClusterClass::reconcile,
@@ -418,3 +794,103 @@ fn error_policy(doc: Arc<impl kube::Resource>, error: &Error, ctx: Arc<Context>)
     ctx.metrics.reconcile_failure(doc, error);
     Action::requeue(Duration::from_secs(10))
 }
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt as _;
+    use k8s_openapi::api::core::v1::Secret;
+    use kube::ResourceExt as _;
+    use kube::api::ObjectMeta;
+    use kube::runtime::{WatchStreamExt as _, reflector, watcher::Event};
+
+    use super::{label_changes, select_watchers};
+
+    fn secret(namespace: &str) -> Secret {
+        Secret {
+            metadata: ObjectMeta {
+                name: Some("kubeconfig".into()),
+                namespace: Some(namespace.into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_select_watchers() {
+        let list = |namespace| {
+            futures::stream::iter([
+                Ok(Event::Init),
+                Ok(Event::InitApply(secret(namespace))),
+                Ok(Event::InitDone),
+            ])
+            .boxed()
+        };
+
+        // Interleaved initial lists don't replace each other in the shared store
+        let (reader, writer) = reflector::store();
+        let applied = select_watchers(vec![list("a"), list("b")])
+            .reflect(writer)
+            .applied_objects()
+            .count()
+            .await;
+        assert_eq!(2, applied);
+        assert_eq!(2, reader.state().len());
+
+        // Single stream is passed through
+        let (reader, writer) = reflector::store::<Secret>();
+        select_watchers(vec![list("a")])
+            .reflect(writer)
+            .for_each(|_| futures::future::ready(()))
+            .await;
+        assert_eq!(1, reader.state().len());
+
+        // Objects missing from a relist are deleted
+        let mut removed = secret("a");
+        removed.metadata.name = Some("removed".into());
+        let relist = futures::stream::iter([
+            Ok(Event::Init),
+            Ok(Event::InitApply(secret("a"))),
+            Ok(Event::InitApply(removed)),
+            Ok(Event::InitDone),
+            Ok(Event::Init),
+            Ok(Event::InitApply(secret("a"))),
+            Ok(Event::InitDone),
+        ])
+        .boxed();
+        let (reader, writer) = reflector::store();
+        select_watchers(vec![relist, list("b")])
+            .reflect(writer)
+            .for_each(|_| futures::future::ready(()))
+            .await;
+        let mut names: Vec<_> = reader
+            .state()
+            .iter()
+            .map(|secret| format!("{}/{}", secret.namespace().unwrap(), secret.name_any()))
+            .collect();
+        names.sort();
+        assert_eq!(vec!["a/kubeconfig", "b/kubeconfig"], names);
+    }
+
+    #[tokio::test]
+    async fn test_label_changes() {
+        let mut heartbeat = secret("a");
+        heartbeat.metadata.resource_version = Some("2".into());
+        let mut labelled = heartbeat.clone();
+        labelled.metadata.labels = Some([("env".to_string(), "dev".to_string())].into());
+
+        let changes: Vec<_> = label_changes(futures::stream::iter([
+            Ok(Event::Init),
+            Ok(Event::InitApply(secret("a"))),
+            Ok(Event::InitDone),
+            Ok(Event::Apply(heartbeat)),
+            Ok(Event::Apply(labelled.clone())),
+            Ok(Event::Delete(labelled)),
+        ]))
+        .collect()
+        .await;
+
+        // Status updates with unchanged labels are skipped
+        assert_eq!(3, changes.len());
+    }
+}
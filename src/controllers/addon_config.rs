@@ -2,15 +2,25 @@ use base64::prelude::*;
 use educe::Educe;
 use futures::StreamExt as _;
 use jiff::Timestamp;
-use std::{fmt::Display, io, str::FromStr, sync::Arc, time::Duration};
+use std::{
+    fmt::Display,
+    io,
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 
 use k8s_openapi::{
-    api::core::v1::{self, ConfigMap, Endpoints, ObjectReference},
+    api::{
+        core::v1::{self, ConfigMap, ObjectReference, Secret},
+        discovery::v1::EndpointSlice,
+    },
     apimachinery::pkg::apis::meta::v1::{Condition, Time},
 };
 use kube::{
     Api, Resource, ResourceExt,
-    api::{ApiResource, DynamicObject, ObjectMeta, PatchParams, TypeMeta},
+    api::{ApiResource, DynamicObject, ListParams, ObjectMeta, Patch, PatchParams, TypeMeta},
     client::scope::Namespace,
     core::object::HasSpec,
     runtime::{
@@ -18,30 +28,41 @@ use kube::{
         watcher::{self, Config, Event},
     },
 };
+use semver::Version;
 use serde::{Deserialize, Serialize, de::DeserializeOwned, ser};
-use serde_json::Value;
+use serde_json::{Value, json};
 use serde_with::{DisplayFromStr, serde_as};
 use thiserror::Error;
-use tracing::{Span, field::display, info, instrument};
+use tracing::{Span, debug, field::display, info, instrument};
 
 use crate::{
     api::{
         capi_cluster::Cluster,
         comparable::ResourceDiff,
         fleet_addon_config::{
-            FeatureGates, FleetAddonConfig, FleetSettings, Install, InstallOptions, Server,
+            AddressFamily, ChartOperationRecord, FeatureFlagError, FeatureGates, FleetAddonConfig,
+            FleetAddonConfigStatus, FleetSettings, Install, InstallOptions, OperationLog,
+            OperationOutcome, Server,
         },
+        fleet_cluster,
     },
+    controller::HelmInstaller,
     telemetry,
 };
 
 use super::{
-    PatchError,
+    CertificateError, MaintenanceWindowError, PatchError,
+    certificate::ca_bundle,
+    conditions,
     controller::{Context, patch},
+    dry_run::DryRunOperation,
     helm::{
-        self,
+        self, compatibility,
         install::{ChartSearch, FleetChart, HelmOperation},
+        runner::{HelmOutput, HelmRunner, JobRunner},
+        version,
     },
+    maintenance,
 };
 
 #[derive(Resource, Serialize, Deserialize, Default, Clone, Debug)]
@@ -107,58 +128,214 @@ struct CertData {
     ca_crt: String,
 }
 
+pub static SERVER_CA_VALID_CONDITION: &str = "ServerCAValid";
+pub static PENDING_UPGRADE_CONDITION: &str = "PendingUpgrade";
+pub static FLEET_COMPATIBLE_CONDITION: &str = "FleetCompatible";
+pub static UNINSTALLED_CONDITION: &str = "Uninstalled";
+pub static FLEET_UNINSTALL_FINALIZER: &str = "uninstall.fleet.addons.cluster.x-k8s.io";
+
+const DEFAULT_API_SERVER_PORT: u16 = 6443;
+/// Namespace of the `kubernetes` Service, exposing the local API server endpoints.
+const KUBERNETES_SERVICE_NAMESPACE: &str = "default";
+/// Namespace of the `kubeadm-config` `ConfigMap`.
+const KUBEADM_CONFIG_NAMESPACE: &str = "kube-system";
+
+/// Size limit of the helm operation output stored in the status.
+const OPERATION_LOG_LIMIT: usize = 4096;
+
+/// Treats a lookup denied by RBAC as a missing object, for the optional lookups
+/// outside of the watched namespaces.
+fn permitted<T>(result: kube::Result<T>) -> kube::Result<Option<T>> {
+    match result {
+        Ok(result) => Ok(Some(result)),
+        Err(kube::Error::Api(e)) if e.is_forbidden() => {
+            debug!("Skipping lookup without access: {}", e.message);
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Wraps IPv6 address literals in brackets, for use in a URL.
+fn bracket_ipv6(address: &str) -> String {
+    match address.parse::<IpAddr>() {
+        Ok(IpAddr::V6(ip)) => format!("[{ip}]"),
+        _ => address.to_string(),
+    }
+}
+
+/// Adds the default API server port to an endpoint without one, bracketing IPv6 literals.
+fn with_default_port(endpoint: &str) -> String {
+    if endpoint.parse::<SocketAddr>().is_ok() {
+        return endpoint.to_string();
+    }
+
+    match endpoint.parse::<IpAddr>() {
+        Ok(IpAddr::V6(ip)) => format!("[{ip}]:{DEFAULT_API_SERVER_PORT}"),
+        Ok(IpAddr::V4(ip)) => format!("{ip}:{DEFAULT_API_SERVER_PORT}"),
+        Err(_) if endpoint.contains(':') => endpoint.to_string(),
+        Err(_) => format!("{endpoint}:{DEFAULT_API_SERVER_PORT}"),
+    }
+}
+
+/// Records the helm operation outcome in the status, failing on an unsuccessful operation.
+fn record_operation(
+    status: &mut FleetAddonConfigStatus,
+    output: HelmOutput,
+) -> AddonConfigSyncResult<()> {
+    let mut start = output.logs.len().saturating_sub(OPERATION_LOG_LIMIT);
+    while !output.logs.is_char_boundary(start) {
+        start += 1;
+    }
+
+    let command = format!("helm {}", output.args.join(" "));
+    status.last_operation = Some(OperationLog {
+        command: command.clone(),
+        job: output.job,
+        succeeded: output.success,
+        logs: output.logs[start..].to_string(),
+    });
+
+    if !output.success {
+        return Err(AddonConfigSyncError::OperationFailed(command));
+    }
+
+    Ok(())
+}
+
+/// Runs the chart operation, recording it in the status history and as the last operation.
+async fn run_operation<E>(
+    status: &mut FleetAddonConfigStatus,
+    chart: &str,
+    operation: &HelmOperation,
+    versions: (Option<&str>, Option<&str>),
+    run: impl Future<Output = Result<HelmOutput, E>>,
+) -> AddonConfigSyncResult<()>
+where
+    AddonConfigSyncError: From<E>,
+{
+    let start_time = Time(Timestamp::now());
+    let result = match run.await {
+        Ok(output) => record_operation(status, output),
+        Err(e) => Err(e.into()),
+    };
+
+    let (from_version, to_version) = versions;
+    status.record_history(ChartOperationRecord {
+        chart: chart.into(),
+        operation: operation.to_string(),
+        from_version: from_version.map(Into::into),
+        to_version: to_version.map(Into::into),
+        start_time,
+        end_time: Time(Timestamp::now()),
+        outcome: match result {
+            Ok(()) => OperationOutcome::Succeeded,
+            Err(_) => OperationOutcome::Failed,
+        },
+        error: result.as_ref().err().map(ToString::to_string),
+    });
+
+    result
+}
+
+/// Records the deferred upgrade and requeues at the next maintenance window start.
+fn defer_upgrade(
+    status: &mut FleetAddonConfigStatus,
+    generation: Option<i64>,
+    target: &str,
+    window: Timestamp,
+) -> Action {
+    let now = Timestamp::now();
+    conditions::set_condition(
+        &mut status.conditions,
+        Condition {
+            last_transition_time: Time(now),
+            message: format!(
+                "Upgrade to version {target} is deferred until the next maintenance window at {window}"
+            ),
+            observed_generation: generation,
+            reason: "OutsideMaintenanceWindow".into(),
+            status: "True".into(),
+            type_: PENDING_UPGRADE_CONDITION.into(),
+        },
+    );
+
+    Action::requeue(Duration::try_from(window.duration_since(now)).unwrap_or_default())
+}
+
+fn reference_name(kind: &str, reference: &ObjectReference) -> String {
+    format!(
+        "{kind} {}/{}",
+        reference.namespace.as_deref().unwrap_or_default(),
+        reference.name.as_deref().unwrap_or_default()
+    )
+}
+
 impl FleetAddonConfig {
     #[instrument(skip_all, fields(reconcile_id, name = self.name_any(), namespace = self.namespace()))]
     pub async fn reconcile_helm(&mut self, ctx: Arc<Context>) -> crate::Result<Action> {
         let _current = Span::current().record("reconcile_id", display(telemetry::get_trace_id()));
+        if let Some(requeue) = self.reconcile_uninstall(ctx.clone()).await? {
+            return Ok(requeue);
+        }
+
         if let Some(requeue) = self.update_flags(ctx.clone()).await? {
             return Ok(requeue);
         }
 
         let chart = FleetChart {
             repo: "https://rancher.github.io/fleet-helm-charts/".into(),
-            namespace: "cattle-fleet-system".into(),
+            namespace: ctx.flags.fleet_namespace.clone(),
             wait: true,
             update_dependency: true,
             create_namespace: true,
             bootstrap_local_cluster: self.spec.bootstrap().unwrap_or_default(),
             feature_gates: self.spec.feature_gates().cloned().unwrap_or_default(),
             version: Option::default(),
+            runner: self.helm_runner(&ctx),
         };
 
         let status = self.status.get_or_insert_default();
         chart.add_repo()?.wait().await?;
 
-        status.conditions.push(Condition {
-            last_transition_time: Time(Timestamp::now()),
-            message: format!("Repo added: {}", chart.repo),
-            observed_generation: self.metadata.generation,
-            reason: "RepoAdd".into(),
-            status: "True".into(),
-            type_: "RepoAdd".into(),
-        });
+        conditions::set_condition(
+            &mut status.conditions,
+            Condition {
+                last_transition_time: Time(Timestamp::now()),
+                message: format!("Repo added: {}", chart.repo),
+                observed_generation: self.metadata.generation,
+                reason: "RepoAdd".into(),
+                status: "True".into(),
+                type_: "RepoAdd".into(),
+            },
+        );
 
         chart.update_repo()?.wait().await?;
 
-        status.conditions.push(Condition {
-            last_transition_time: Time(Timestamp::now()),
-            message: format!("Repo updated: {}", chart.repo),
-            observed_generation: self.metadata.generation,
-            reason: "RepoUpdate".into(),
-            status: "True".into(),
-            type_: "RepoUpdate".into(),
-        });
+        conditions::set_condition(
+            &mut status.conditions,
+            Condition {
+                last_transition_time: Time(Timestamp::now()),
+                message: format!("Repo updated: {}", chart.repo),
+                observed_generation: self.metadata.generation,
+                reason: "RepoUpdate".into(),
+                status: "True".into(),
+                type_: "RepoUpdate".into(),
+            },
+        );
 
-        if let Some(install) = &self.spec.install {
-            if let Some(requeue) = self
-                .install_fleet(FleetChart {
-                    version: Some(install.install_version.clone()),
-                    ..chart.clone()
-                })
+        if let Some(install) = &self.spec.install
+            && let Some(requeue) = self
+                .install_fleet(
+                    ctx.clone(),
+                    FleetChart {
+                        version: Some(install.install_version.clone()),
+                        ..chart.clone()
+                    },
+                )
                 .await?
-            {
-                return Ok(requeue);
-            }
+        {
+            return Ok(requeue);
         }
 
         return Ok(Action::await_change());
@@ -170,8 +347,9 @@ impl FleetAddonConfig {
         ctx: Arc<Context>,
     ) -> ReconcileConfigSyncResult<Action> {
         let _current = Span::current().record("reconcile_id", display(telemetry::get_trace_id()));
-        let ns = Namespace::from("cattle-fleet-system");
-        let mut fleet_config: FleetConfig = ctx.client.get("fleet-controller", &ns).await?;
+        let ns = Namespace::from(ctx.flags.fleet_namespace.as_str());
+        let mut fleet_config: FleetConfig =
+            ctx.client.get(&ctx.flags.fleet_config_name, &ns).await?;
 
         if let Some(server) = self.spec().config.as_ref().and_then(|c| c.server.as_ref()) {
             self.update_certificate(ctx.clone(), &mut fleet_config, server)
@@ -215,37 +393,56 @@ impl FleetAddonConfig {
         let mut stream = ctx.stream.stream.lock().await;
         stream.clear();
 
-        if ctx.version >= 32 {
-            stream.push(
-                watcher::watcher(
-                    Api::all_with(ctx.client.clone(), &ApiResource::erase::<Cluster>(&())),
-                    cluster_config.streaming_lists(),
-                )
-                .boxed(),
-            );
-
-            stream.push(
-                watcher::watcher(
-                    Api::all_with(
-                        ctx.client.clone(),
-                        &ApiResource::erase::<v1::Namespace>(&()),
-                    ),
-                    ns_config.streaming_lists(),
-                )
-                .boxed(),
-            );
-        } else {
-            stream.push(
-                watcher::watcher(Api::<Cluster>::all(ctx.client.clone()), cluster_config)
-                    .map(to_dynamic_event)
-                    .boxed(),
-            );
-
-            stream.push(
-                watcher::watcher(Api::<v1::Namespace>::all(ctx.client.clone()), ns_config)
-                    .map(to_dynamic_event)
+        for namespace in ctx.flags.watched_namespaces() {
+            // Scoped watches only observe the watched namespace object
+            let ns_config = match namespace {
+                Some(namespace) => ns_config
+                    .clone()
+                    .fields(&format!("metadata.name={namespace}")),
+                None => ns_config.clone(),
+            };
+
+            if ctx.version >= 32 {
+                let cluster_resource = ApiResource::erase::<Cluster>(&());
+                let clusters = match namespace {
+                    Some(namespace) => {
+                        Api::namespaced_with(ctx.client.clone(), namespace, &cluster_resource)
+                    }
+                    None => Api::all_with(ctx.client.clone(), &cluster_resource),
+                };
+                stream.push(
+                    watcher::watcher(clusters, cluster_config.clone().streaming_lists()).boxed(),
+                );
+
+                // Namespaces are cluster scoped, scoped watches select the watched namespace
+                // by name, which RBAC grants with `resourceNames` instead of a cluster-wide list
+                stream.push(
+                    watcher::watcher(
+                        Api::all_with(
+                            ctx.client.clone(),
+                            &ApiResource::erase::<v1::Namespace>(&()),
+                        ),
+                        ns_config.streaming_lists(),
+                    )
                     .boxed(),
-            );
+                );
+            } else {
+                let clusters = match namespace {
+                    Some(namespace) => Api::<Cluster>::namespaced(ctx.client.clone(), namespace),
+                    None => Api::<Cluster>::all(ctx.client.clone()),
+                };
+                stream.push(
+                    watcher::watcher(clusters, cluster_config.clone())
+                        .map(to_dynamic_event)
+                        .boxed(),
+                );
+
+                stream.push(
+                    watcher::watcher(Api::<v1::Namespace>::all(ctx.client.clone()), ns_config)
+                        .map(to_dynamic_event)
+                        .boxed(),
+                );
+            }
         }
 
         info!(
@@ -265,21 +462,61 @@ impl FleetAddonConfig {
         Ok(Action::await_change())
     }
 
-    fn default_endpoint_lookup(endpoints: Endpoints) -> Option<String> {
-        let subsets = endpoints.subsets?;
-        let subnet = subsets.first()?.clone();
-        let addresses = subnet.addresses?;
-        let ports = subnet.ports?;
-        let address = addresses.first()?.clone();
-        let port = ports.first()?.clone();
-
-        let url = address.hostname.or(Some(address.ip))?;
-        let name = port.name;
-        let port = port.port;
-        match name {
-            Some(name) => Some(format!("{name}://{url}:{port}")),
-            None => Some(url),
+    /// Infers the API server URL from the `default/kubernetes` service `EndpointSlices`.
+    ///
+    /// Ready endpoints of the preferred address family are selected first. The `kubeadm`
+    /// control plane endpoint takes precedence over the endpoint addresses, if provided.
+    fn infer_server_url(
+        slices: &[EndpointSlice],
+        family: Option<AddressFamily>,
+        kubeadm_endpoint: Option<&str>,
+    ) -> Option<String> {
+        if let Some(endpoint) = kubeadm_endpoint {
+            return Some(format!("https://{}", with_default_port(endpoint)));
         }
+
+        let mut slices: Vec<&EndpointSlice> = slices
+            .iter()
+            .filter(|slice| matches!(slice.address_type.as_str(), "IPv4" | "IPv6"))
+            .collect();
+        // Stable sort keeps the API order within the same family
+        slices.sort_by_key(|slice| family.is_none_or(|f| slice.address_type != f.as_str()));
+
+        slices.into_iter().find_map(|slice| {
+            let ports = slice.ports.as_ref()?;
+            let port = ports
+                .iter()
+                .find(|port| port.name.as_deref() == Some("https"))
+                .or(ports.first())?
+                .port?;
+
+            let address = slice
+                .endpoints
+                .iter()
+                .filter(|endpoint| {
+                    endpoint
+                        .conditions
+                        .as_ref()
+                        .and_then(|conditions| conditions.ready)
+                        .unwrap_or(true)
+                })
+                .find_map(|endpoint| endpoint.addresses.first())?;
+
+            Some(format!("https://{}:{port}", bracket_ipv6(address)))
+        })
+    }
+
+    /// Reads the `controlPlaneEndpoint` from the `kubeadm-config` `ClusterConfiguration`.
+    fn kubeadm_endpoint(kubeadm_config: &ConfigMap) -> Option<String> {
+        let cluster_configuration = kubeadm_config.data.as_ref()?.get("ClusterConfiguration")?;
+        let cluster_configuration: serde_yaml::Value =
+            serde_yaml::from_str(cluster_configuration).ok()?;
+
+        cluster_configuration
+            .get("controlPlaneEndpoint")?
+            .as_str()
+            .filter(|endpoint| !endpoint.is_empty())
+            .map(ToString::to_string)
     }
 
     async fn update_certificate(
@@ -288,18 +525,136 @@ impl FleetAddonConfig {
         fleet_config: &mut FleetConfig,
         fleet_install: &Server,
     ) -> AddonConfigSyncResult<()> {
-        let ns = Namespace::from("default");
-        let cert_config_map: CertConfigMap = match fleet_install {
-            Server::InferLocal(true) => ctx.client.get("kube-root-ca.crt", &ns).await?,
-            Server::Custom(InstallOptions {
-                api_server_ca_config_ref: Some(config_ref),
-                ..
-            }) => ctx.client.fetch(config_ref).await?,
-            _ => return Ok(()),
+        let ns = Namespace::from(ctx.flags.ca_namespace.as_str());
+        let sources = match fleet_install {
+            Server::InferLocal(true) => {
+                let cert_config_map: CertConfigMap =
+                    ctx.client.get("kube-root-ca.crt", &ns).await?;
+                vec![cert_config_map.data.ca_crt]
+            }
+            Server::Custom(options) => Self::ca_sources(ctx.clone(), options).await?,
+            Server::InferLocal(false) => vec![],
+        };
+
+        if sources.is_empty() {
+            return Ok(());
+        }
+
+        let condition = match ca_bundle(&sources) {
+            Ok(bundle) => {
+                fleet_config.data.config.api_server_ca = BASE64_STANDARD.encode(bundle);
+                Ok(Condition {
+                    last_transition_time: Time(Timestamp::now()),
+                    message: "API server CA bundle is valid".into(),
+                    observed_generation: None,
+                    reason: "Valid".into(),
+                    status: "True".into(),
+                    type_: SERVER_CA_VALID_CONDITION.into(),
+                })
+            }
+            Err(e) => Err(Condition {
+                last_transition_time: Time(Timestamp::now()),
+                message: format!("Invalid API server CA: {e}"),
+                observed_generation: None,
+                reason: "InvalidCertificate".into(),
+                status: "False".into(),
+                type_: SERVER_CA_VALID_CONDITION.into(),
+            })
+            .map_err(|condition| (condition, e)),
         };
 
-        fleet_config.data.config.api_server_ca =
-            BASE64_STANDARD.encode(cert_config_map.data.ca_crt);
+        match condition {
+            Ok(condition) => self
+                .set_condition(ctx, condition)
+                .await
+                .map_err(AddonConfigSyncError::ConditionUpdate),
+            Err((condition, e)) => {
+                self.set_condition(ctx, condition)
+                    .await
+                    .map_err(AddonConfigSyncError::ConditionUpdate)?;
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Collects the PEM encoded CA certificates from all configured sources.
+    async fn ca_sources(
+        ctx: Arc<Context>,
+        options: &InstallOptions,
+    ) -> AddonConfigSyncResult<Vec<String>> {
+        let key = options.api_server_ca_key.as_deref().unwrap_or("ca.crt");
+        let mut sources = vec![];
+
+        if let Some(config_ref) = &options.api_server_ca_config_ref {
+            let config_map: ConfigMap = ctx.client.fetch(config_ref).await?;
+            let ca = config_map
+                .data
+                .unwrap_or_default()
+                .remove(key)
+                .ok_or_else(|| CertificateError::MissingKey {
+                    key: key.to_string(),
+                    source_ref: reference_name("ConfigMap", config_ref),
+                })?;
+            sources.push(ca);
+        }
+
+        if let Some(secret_ref) = &options.api_server_ca_secret_ref {
+            let secret: Secret = ctx.client.fetch(secret_ref).await?;
+            let ca = secret.data.unwrap_or_default().remove(key).ok_or_else(|| {
+                CertificateError::MissingKey {
+                    key: key.to_string(),
+                    source_ref: reference_name("Secret", secret_ref),
+                }
+            })?;
+            sources.push(
+                String::from_utf8(ca.0).map_err(|_| CertificateError::Encoding {
+                    source_ref: reference_name("Secret", secret_ref),
+                })?,
+            );
+        }
+
+        if let Some(ca) = &options.api_server_ca {
+            sources.push(ca.clone());
+        }
+
+        Ok(sources)
+    }
+
+    /// Sets a condition in the `FleetAddonConfig` status, owned by the config controller.
+    ///
+    /// The status is only patched when the condition differs from the observed one.
+    /// The condition is applied by the field manager of its type, so the concurrent status
+    /// applies of the helm controller neither conflict with it nor remove it.
+    async fn set_condition(&self, ctx: Arc<Context>, mut condition: Condition) -> kube::Result<()> {
+        let existing = self.status.as_ref().and_then(|status| {
+            status
+                .conditions
+                .iter()
+                .find(|c| c.type_ == condition.type_)
+        });
+
+        condition.observed_generation = self.metadata.generation;
+        let Some(condition) = conditions::merge(existing, condition) else {
+            return Ok(());
+        };
+        if ctx.flags.dry_run {
+            return Ok(());
+        }
+
+        let patch = json!({
+            "apiVersion": FleetAddonConfig::api_version(&()),
+            "kind": FleetAddonConfig::kind(&()),
+            "status": {
+                "conditions": [condition],
+            },
+        });
+        Api::<FleetAddonConfig>::all(ctx.client.clone())
+            .patch_status(
+                &self.name_any(),
+                &PatchParams::apply(&conditions::field_manager(&condition.type_)).force(),
+                &Patch::Apply(&patch),
+            )
+            .await?;
 
         Ok(())
     }
@@ -312,15 +667,45 @@ impl FleetAddonConfig {
     ) -> AddonConfigSyncResult<()> {
         let api_server_url = match fleet_install {
             Server::InferLocal(true) => {
-                if let Some(api_server_url) = {
-                    let ns = Namespace::from("default");
-                    FleetAddonConfig::default_endpoint_lookup(
-                        ctx.client.get("kubernetes", &ns).await?,
-                    )
-                } {
-                    api_server_url
+                let inference = self
+                    .spec
+                    .config
+                    .as_ref()
+                    .and_then(|config| config.server_inference.clone())
+                    .unwrap_or_default();
+
+                // Scoped controllers may not be granted access to the cluster namespaces
+                let kubeadm_endpoint = if inference.kubeadm_endpoint.unwrap_or_default() {
+                    permitted(
+                        Api::<ConfigMap>::namespaced(ctx.client.clone(), KUBEADM_CONFIG_NAMESPACE)
+                            .get_opt("kubeadm-config")
+                            .await,
+                    )?
+                    .flatten()
+                    .as_ref()
+                    .and_then(Self::kubeadm_endpoint)
                 } else {
-                    return Ok(());
+                    None
+                };
+
+                let slices = permitted(
+                    Api::<EndpointSlice>::namespaced(
+                        ctx.client.clone(),
+                        KUBERNETES_SERVICE_NAMESPACE,
+                    )
+                    .list(&ListParams::default().labels("kubernetes.io/service-name=kubernetes"))
+                    .await,
+                )?
+                .map(|slices| slices.items)
+                .unwrap_or_default();
+
+                match Self::infer_server_url(
+                    &slices,
+                    inference.address_family,
+                    kubeadm_endpoint.as_deref(),
+                ) {
+                    Some(api_server_url) => api_server_url,
+                    None => return Ok(()),
                 }
             }
             Server::Custom(InstallOptions {
@@ -335,12 +720,44 @@ impl FleetAddonConfig {
         Ok(())
     }
 
-    async fn install_fleet(&mut self, chart: FleetChart) -> AddonConfigSyncResult<Option<Action>> {
+    async fn install_fleet(
+        &mut self,
+        ctx: Arc<Context>,
+        mut chart: FleetChart,
+    ) -> AddonConfigSyncResult<Option<Action>> {
+        let resolved_version = match chart.version.as_ref() {
+            Some(Install::Constraint(constraint)) => {
+                let available = chart.search_versions("fleet/fleet").await?;
+                let resolved =
+                    version::resolve(constraint, available.iter().map(|c| c.version.as_str()))?
+                        .to_string();
+                chart.version = Some(Install::Version(resolved.clone()));
+                Some(resolved)
+            }
+            _ => None,
+        };
+        self.status.get_or_insert_default().resolved_version = resolved_version;
+
         let expected_version = match chart.version.as_ref() {
             Some(version) => &version.clone().normalized(),
             None => return Ok(None),
         };
 
+        let windows = self
+            .spec
+            .install
+            .as_ref()
+            .and_then(|install| install.maintenance_windows.clone())
+            .unwrap_or_default();
+        let next_window = maintenance::next_window(&windows, Timestamp::now())?;
+
+        if let Some(requeue) = self
+            .check_compatibility(&chart, expected_version, &ctx.kube_version)
+            .await?
+        {
+            return Ok(Some(requeue));
+        }
+
         let status = self.status.get_or_insert_default();
         let installed_chart_meta = FleetChart::get_metadata("fleet-crd").await?;
         let search_result = chart
@@ -352,15 +769,65 @@ impl FleetAddonConfig {
             (Some(installed), Some(search), Install::FollowLatest(true))
                 if search.app_version != installed.app_version =>
             {
-                chart.fleet_crds(&HelmOperation::Upgrade)?.wait().await?;
+                if let Some(window) = next_window {
+                    let generation = self.metadata.generation;
+                    return Ok(Some(defer_upgrade(
+                        status,
+                        generation,
+                        &search.app_version,
+                        window,
+                    )));
+                }
+                let operation = HelmOperation::Upgrade;
+                let versions = (
+                    Some(installed.app_version.as_str()),
+                    Some(search.app_version.as_str()),
+                );
+                run_operation(
+                    status,
+                    "fleet-crd",
+                    &operation,
+                    versions,
+                    chart.fleet_crds(&operation),
+                )
+                .await?;
             }
             (Some(installed), Some(_), Install::Version(expected))
                 if expected.strip_prefix("v").unwrap_or(expected) != installed.app_version =>
             {
-                chart.fleet_crds(&HelmOperation::Upgrade)?.wait().await?;
+                if let Some(window) = next_window {
+                    let generation = self.metadata.generation;
+                    return Ok(Some(defer_upgrade(status, generation, expected, window)));
+                }
+                let operation = HelmOperation::Upgrade;
+                let versions = (
+                    Some(installed.app_version.as_str()),
+                    Some(expected.as_str()),
+                );
+                run_operation(
+                    status,
+                    "fleet-crd",
+                    &operation,
+                    versions,
+                    chart.fleet_crds(&operation),
+                )
+                .await?;
             }
-            (None, Some(_), _) => {
-                chart.fleet_crds(&HelmOperation::Install)?.wait().await?;
+            (None, Some(search), expected) => {
+                let target = match expected {
+                    Install::Version(version) => version,
+                    _ => &search.app_version,
+                };
+                let operation = HelmOperation::Install;
+                let versions = (None, Some(target.as_str()));
+                run_operation(
+                    status,
+                    "fleet-crd",
+                    &operation,
+                    versions,
+                    chart.fleet_crds(&operation),
+                )
+                .await?;
             }
             (Some(_), Some(_), Install::FollowLatest(false) | Install::Version(_)) => {}
             (_, _, _) => return Ok(Some(Action::requeue(Duration::from_secs(10)))),
@@ -381,42 +848,97 @@ impl FleetAddonConfig {
             (Some(installed), Some(search), Install::FollowLatest(true))
                 if search.app_version != installed.app_version =>
             {
-                chart.fleet(&HelmOperation::Upgrade)?.wait().await?;
-                status.conditions.push(Condition {
-                    last_transition_time: Time(Timestamp::now()),
-                    message: format!("Updated fleet to version {}", search.app_version),
-                    observed_generation: self.metadata.generation,
-                    reason: "Installed".into(),
-                    status: "True".into(),
-                    type_: "Installed".into(),
-                });
+                if let Some(window) = next_window {
+                    let generation = self.metadata.generation;
+                    return Ok(Some(defer_upgrade(
+                        status,
+                        generation,
+                        &search.app_version,
+                        window,
+                    )));
+                }
+                let operation = HelmOperation::Upgrade;
+                let versions = (
+                    Some(installed.app_version.as_str()),
+                    Some(search.app_version.as_str()),
+                );
+                run_operation(
+                    status,
+                    "fleet",
+                    &operation,
+                    versions,
+                    chart.fleet(&operation),
+                )
+                .await?;
+                conditions::set_condition(
+                    &mut status.conditions,
+                    Condition {
+                        last_transition_time: Time(Timestamp::now()),
+                        message: format!("Updated fleet to version {}", search.app_version),
+                        observed_generation: self.metadata.generation,
+                        reason: "Installed".into(),
+                        status: "True".into(),
+                        type_: "Installed".into(),
+                    },
+                );
                 status.installed_version = search.app_version.clone().into();
             }
             (Some(installed), Some(_), Install::Version(expected))
                 if expected.strip_prefix("v").unwrap_or(expected) != installed.app_version =>
             {
-                chart.fleet(&HelmOperation::Upgrade)?.wait().await?;
-                status.conditions.push(Condition {
-                    last_transition_time: Time(Timestamp::now()),
-                    message: format!("Updated fleet to version {expected}"),
-                    observed_generation: self.metadata.generation,
-                    reason: "Installed".into(),
-                    status: "True".into(),
-                    type_: "Installed".into(),
-                });
+                if let Some(window) = next_window {
+                    let generation = self.metadata.generation;
+                    return Ok(Some(defer_upgrade(status, generation, expected, window)));
+                }
+                let operation = HelmOperation::Upgrade;
+                let versions = (
+                    Some(installed.app_version.as_str()),
+                    Some(expected.as_str()),
+                );
+                run_operation(
+                    status,
+                    "fleet",
+                    &operation,
+                    versions,
+                    chart.fleet(&operation),
+                )
+                .await?;
+                conditions::set_condition(
+                    &mut status.conditions,
+                    Condition {
+                        last_transition_time: Time(Timestamp::now()),
+                        message: format!("Updated fleet to version {expected}"),
+                        observed_generation: self.metadata.generation,
+                        reason: "Installed".into(),
+                        status: "True".into(),
+                        type_: "Installed".into(),
+                    },
+                );
                 status.installed_version = expected.clone().into();
             }
             (None, Some(ChartSearch { app_version, .. }), Install::FollowLatest(_))
             | (None, Some(_), Install::Version(app_version)) => {
-                chart.fleet(&HelmOperation::Install)?.wait().await?;
-                status.conditions.push(Condition {
-                    last_transition_time: Time(Timestamp::now()),
-                    message: format!("Installed fleet version {app_version}"),
-                    observed_generation: self.metadata.generation,
-                    reason: "Installed".into(),
-                    status: "True".into(),
-                    type_: "Installed".into(),
-                });
+                let operation = HelmOperation::Install;
+                let versions = (None, Some(app_version.as_str()));
+                run_operation(
+                    status,
+                    "fleet",
+                    &operation,
+                    versions,
+                    chart.fleet(&operation),
+                )
+                .await?;
+                conditions::set_condition(
+                    &mut status.conditions,
+                    Condition {
+                        last_transition_time: Time(Timestamp::now()),
+                        message: format!("Installed fleet version {app_version}"),
+                        observed_generation: self.metadata.generation,
+                        reason: "Installed".into(),
+                        status: "True".into(),
+                        type_: "Installed".into(),
+                    },
+                );
                 status.installed_version = app_version.clone().into();
             }
             (Some(installed), Some(_), Install::FollowLatest(false)) => {
@@ -426,34 +948,283 @@ impl FleetAddonConfig {
             (_, _, _) => return Ok(Some(Action::requeue(Duration::from_secs(10)))),
         }
 
+        conditions::remove_condition(&mut status.conditions, PENDING_UPGRADE_CONDITION);
+
         Ok(None)
     }
 
-    async fn update_flags(&mut self, ctx: Arc<Context>) -> FleetPatchResult<Option<Action>> {
-        if let Some(feature_gates) = self.spec.feature_gates() {
-            if let Some(reference) = feature_gates.config_map_ref() {
-                feature_gates
-                    .update_config_map(ctx.clone(), reference)
-                    .await?;
-
-                self.status
-                    .get_or_insert_default()
-                    .conditions
-                    .push(Condition {
-                        last_transition_time: Time(Timestamp::now()),
-                        message: format!(
-                            "Updated chart flags to the expected state: {feature_gates}"
-                        ),
-                        observed_generation: self.metadata.generation,
-                        reason: "FlagsUpdate".into(),
-                        status: "True".into(),
-                        type_: "FlagsUpdate".into(),
-                    });
+    /// Maintains the uninstall finalizer, and uninstalls Fleet on removal if the policy allows it.
+    ///
+    /// Returns an action if the reconcile should stop, as the config is being deleted
+    /// or the uninstall is waiting for imported clusters to be removed.
+    async fn reconcile_uninstall(
+        &mut self,
+        ctx: Arc<Context>,
+    ) -> AddonConfigSyncResult<Option<Action>> {
+        let enabled = self.spec.uninstall_enabled();
+        let deleting = self.metadata.deletion_timestamp.is_some();
+        let finalized = self
+            .finalizers()
+            .iter()
+            .any(|f| f == FLEET_UNINSTALL_FINALIZER);
+
+        match (enabled, deleting, finalized) {
+            (true, false, false) => {
+                self.finalizers_mut()
+                    .push(FLEET_UNINSTALL_FINALIZER.to_string());
+                self.patch_finalizers(ctx).await?;
+            }
+            (false, _, true) => {
+                self.finalizers_mut()
+                    .retain(|f| f != FLEET_UNINSTALL_FINALIZER);
+                self.patch_finalizers(ctx).await?;
+            }
+            (true, true, true) => {
+                if let Some(requeue) = self.uninstall_fleet(ctx.clone()).await? {
+                    return Ok(Some(requeue));
+                }
+
+                self.finalizers_mut()
+                    .retain(|f| f != FLEET_UNINSTALL_FINALIZER);
+                self.patch_finalizers(ctx).await?;
+            }
+            (true, false, true)
+                if self.spec.install.is_none()
+                    && self
+                        .status
+                        .as_ref()
+                        .is_some_and(|s| s.installed_version.is_some()) =>
+            {
+                return self.uninstall_fleet(ctx).await;
+            }
+            _ => {}
+        }
+
+        Ok(deleting.then(Action::await_change))
+    }
+
+    /// Uninstalls the `fleet` and `fleet-crd` releases, in this order, once no Fleet clusters
+    /// imported by the provider remain, unless forced.
+    async fn uninstall_fleet(
+        &mut self,
+        ctx: Arc<Context>,
+    ) -> AddonConfigSyncResult<Option<Action>> {
+        let chart = FleetChart {
+            runner: self.helm_runner(&ctx),
+            ..Default::default()
+        };
+        let mut clusters = vec![];
+        for api in ctx
+            .flags
+            .watched_apis::<fleet_cluster::Cluster>(&ctx.client)
+        {
+            let list = api
+                .list(&ListParams::default())
+                .await
+                .map_err(AddonConfigSyncError::ClusterLookup)?;
+            clusters.extend(list.items);
+        }
+        let imported: Vec<String> = clusters
+            .iter()
+            .filter(|cluster| fleet_cluster::source_cluster_ref(*cluster).is_some())
+            .map(|cluster| {
+                format!(
+                    "{}/{}",
+                    cluster.namespace().unwrap_or_default(),
+                    cluster.name_any()
+                )
+            })
+            .collect();
+
+        let generation = self.metadata.generation;
+        let status = self.status.get_or_insert_default();
+        if !imported.is_empty() && !self.spec.uninstall_forced() {
+            let mut clusters = imported
+                .iter()
+                .take(5)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ");
+            if imported.len() > 5 {
+                clusters.push_str(", ...");
+            }
+
+            conditions::set_condition(
+                &mut status.conditions,
+                Condition {
+                    last_transition_time: Time(Timestamp::now()),
+                    message: format!(
+                        "Waiting for {} imported Fleet clusters to be removed before uninstalling Fleet: {clusters}",
+                        imported.len()
+                    ),
+                    observed_generation: generation,
+                    reason: "ImportedClustersRemain".into(),
+                    status: "False".into(),
+                    type_: UNINSTALLED_CONDITION.into(),
+                },
+            );
+            return Ok(Some(Action::requeue(Duration::from_secs(30))));
+        }
 
-                return Ok(Some(Action::await_change()));
+        for release in ["fleet", "fleet-crd"] {
+            if let Some(installed) = FleetChart::get_metadata(release).await? {
+                info!("Uninstalling {release} release");
+                let operation = HelmOperation::Uninstall;
+                let versions = (Some(installed.app_version.as_str()), None);
+                run_operation(
+                    status,
+                    release,
+                    &operation,
+                    versions,
+                    chart.uninstall(&installed),
+                )
+                .await?;
             }
         }
 
+        status.installed_version = None;
+        status.resolved_version = None;
+        conditions::set_condition(
+            &mut status.conditions,
+            Condition {
+                last_transition_time: Time(Timestamp::now()),
+                message: "Uninstalled fleet and fleet-crd releases".into(),
+                observed_generation: generation,
+                reason: "Uninstalled".into(),
+                status: "True".into(),
+                type_: UNINSTALLED_CONDITION.into(),
+            },
+        );
+
+        Ok(None)
+    }
+
+    /// Helm runner selected by the controller flags. Jobs are owned by the config.
+    ///
+    /// In the dry-run mode the mutating helm operations are recorded instead.
+    fn helm_runner(&self, ctx: &Context) -> HelmRunner {
+        if ctx.flags.dry_run {
+            return HelmRunner::DryRun(ctx.dry_run.clone());
+        }
+
+        match ctx.flags.helm_installer {
+            HelmInstaller::Process => HelmRunner::Process,
+            HelmInstaller::Job => HelmRunner::Job(Box::new(JobRunner {
+                client: ctx.client.clone(),
+                namespace: ctx.flags.helm_job_namespace.clone(),
+                service_account: ctx.flags.helm_job_service_account.clone(),
+                image: ctx.flags.helm_job_image.clone(),
+                owner: self.controller_owner_ref(&()),
+            })),
+        }
+    }
+
+    async fn patch_finalizers(&self, ctx: Arc<Context>) -> AddonConfigSyncResult<()> {
+        let api = Api::<FleetAddonConfig>::all(ctx.client.clone());
+        let patch = json!({"metadata": {"finalizers": self.finalizers()}});
+        if ctx.flags.dry_run {
+            let live = api
+                .get(&self.name_any())
+                .await
+                .map_err(AddonConfigSyncError::FinalizerPatch)?;
+            ctx.dry_run
+                .merge(&live, DryRunOperation::Finalizers, &patch)
+                .await;
+            return Ok(());
+        }
+
+        api.patch(
+            &self.name_any(),
+            &PatchParams::default(),
+            &Patch::Merge(&patch),
+        )
+        .await
+        .map_err(AddonConfigSyncError::FinalizerPatch)?;
+
+        Ok(())
+    }
+
+    /// Refuses to install or upgrade Fleet to a version incompatible with the management cluster.
+    async fn check_compatibility(
+        &mut self,
+        chart: &FleetChart,
+        expected_version: &Install,
+        kubernetes: &Version,
+    ) -> AddonConfigSyncResult<Option<Action>> {
+        let installed = FleetChart::get_metadata("fleet").await?;
+        let (chart_version, app_version) = match (installed.as_ref(), expected_version) {
+            (_, Install::Version(version)) => (version.clone(), version.clone()),
+            (None, Install::FollowLatest(_)) | (Some(_), Install::FollowLatest(true)) => {
+                let search = chart.search_repo().await?;
+                match search.into_iter().find(|r| r.name == "fleet/fleet") {
+                    Some(latest) => (latest.version, latest.app_version),
+                    None => return Ok(None),
+                }
+            }
+            _ => return Ok(None),
+        };
+
+        if installed.is_some_and(|installed| {
+            installed.app_version == app_version.strip_prefix('v').unwrap_or(&app_version)
+        }) {
+            return Ok(None);
+        }
+
+        let metadata = chart.show_chart("fleet/fleet", &chart_version).await?;
+        let compatibility = compatibility::check(&metadata, kubernetes);
+        let status = self.status.get_or_insert_default();
+        let (condition_status, reason, message, requeue) = match compatibility {
+            Ok(()) => (
+                "True",
+                "Compatible",
+                format!("Fleet version {app_version} is compatible with Kubernetes {kubernetes}"),
+                None,
+            ),
+            Err(blocker) => (
+                "False",
+                "Incompatible",
+                format!("Refusing to install Fleet: {blocker}"),
+                Some(Action::requeue(Duration::from_secs(300))),
+            ),
+        };
+        conditions::set_condition(
+            &mut status.conditions,
+            Condition {
+                last_transition_time: Time(Timestamp::now()),
+                message,
+                observed_generation: self.metadata.generation,
+                reason: reason.into(),
+                status: condition_status.into(),
+                type_: FLEET_COMPATIBLE_CONDITION.into(),
+            },
+        );
+
+        Ok(requeue)
+    }
+
+    async fn update_flags(&mut self, ctx: Arc<Context>) -> FleetPatchResult<Option<Action>> {
+        if let Some(feature_gates) = self.spec.feature_gates()
+            && let Some(reference) = feature_gates.config_map_ref()
+        {
+            feature_gates
+                .update_config_map(ctx.clone(), reference)
+                .await?;
+
+            conditions::set_condition(
+                &mut self.status.get_or_insert_default().conditions,
+                Condition {
+                    last_transition_time: Time(Timestamp::now()),
+                    message: format!("Updated chart flags to the expected state: {feature_gates}"),
+                    observed_generation: self.metadata.generation,
+                    reason: "FlagsUpdate".into(),
+                    status: "True".into(),
+                    type_: "FlagsUpdate".into(),
+                },
+            );
+
+            return Ok(Some(Action::await_change()));
+        }
+
         Ok(None)
     }
 }
@@ -465,7 +1236,7 @@ impl FeatureGates {
         reference: &ObjectReference,
     ) -> ConfigMapSyncResult<()> {
         let mut settings: FleetSettings = ctx.client.fetch(reference).await?;
-        self.merge_features(settings.data.get_or_insert_default());
+        self.merge_features(settings.data.get_or_insert_default())?;
         patch(
             ctx,
             &mut settings,
@@ -566,6 +1337,33 @@ pub enum AddonConfigSyncError {
 
     #[error("Error waiting for command: {0}")]
     CommandError(#[from] io::Error),
+
+    #[error("API server CA error: {0}")]
+    Certificate(#[from] CertificateError),
+
+    #[error("Condition update error: {0}")]
+    ConditionUpdate(#[source] kube::Error),
+
+    #[error("Fleet version resolve error: {0}")]
+    VersionResolve(#[from] helm::VersionResolveError),
+
+    #[error("Maintenance window error: {0}")]
+    MaintenanceWindow(#[from] MaintenanceWindowError),
+
+    #[error("Fleet chart metadata error: {0}")]
+    ChartShow(#[from] helm::ChartShowError),
+
+    #[error("Fleet uninstall error: {0}")]
+    FleetUninstall(#[from] helm::FleetUninstallError),
+
+    #[error("Imported Fleet clusters lookup error: {0}")]
+    ClusterLookup(#[source] kube::Error),
+
+    #[error("Uninstall finalizer update error: {0}")]
+    FinalizerPatch(#[source] kube::Error),
+
+    #[error("Helm operation failed: {0}")]
+    OperationFailed(String),
 }
 
 pub type DynamicWatcherResult<T> = std::result::Result<T, DynamicWatcherError>;
@@ -585,9 +1383,15 @@ pub enum ConfigMapSyncError {
 
     #[error("FleetSettings patch error: {0}")]
     Patch(#[from] PatchError),
+
+    #[error("Fleet feature flags error: {0}")]
+    FeatureFlags(#[from] FeatureFlagError),
 }
 
+#[cfg(test)]
 mod tests {
+    use k8s_openapi::api::discovery::v1::EndpointSlice;
+
     #[test]
     fn test() {
         use crate::controllers::addon_config::FleetConfigData;
@@ -613,4 +1417,139 @@ mod tests {
 
         let _config: FleetConfigData = serde_json::from_str(data).unwrap();
     }
+
+    fn slice(family: &str, addresses: &[(&str, bool)], port: i32) -> EndpointSlice {
+        use k8s_openapi::api::discovery::v1::{Endpoint, EndpointConditions, EndpointPort};
+
+        EndpointSlice {
+            address_type: family.into(),
+            endpoints: addresses
+                .iter()
+                .map(|(address, ready)| Endpoint {
+                    addresses: vec![address.to_string()],
+                    conditions: Some(EndpointConditions {
+                        ready: Some(*ready),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                .collect(),
+            ports: Some(vec![EndpointPort {
+                name: Some("https".into()),
+                port: Some(port),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_infer_server_url() {
+        use crate::api::fleet_addon_config::{AddressFamily, FleetAddonConfig};
+
+        let ipv4 = slice("IPv4", &[("10.0.0.1", true)], 6443);
+        let ipv6 = slice("IPv6", &[("fd00::1", true)], 6443);
+        let not_ready = slice("IPv4", &[("10.0.0.2", false), ("10.0.0.3", true)], 443);
+
+        let cases = [
+            ("no slices", vec![], None, None, None),
+            (
+                "ipv4",
+                vec![ipv4.clone()],
+                None,
+                None,
+                Some("https://10.0.0.1:6443"),
+            ),
+            (
+                "ipv6 is bracketed",
+                vec![ipv6.clone()],
+                None,
+                None,
+                Some("https://[fd00::1]:6443"),
+            ),
+            (
+                "dual stack follows API order",
+                vec![ipv4.clone(), ipv6.clone()],
+                None,
+                None,
+                Some("https://10.0.0.1:6443"),
+            ),
+            (
+                "dual stack prefers ipv6",
+                vec![ipv4.clone(), ipv6.clone()],
+                Some(AddressFamily::IPv6),
+                None,
+                Some("https://[fd00::1]:6443"),
+            ),
+            (
+                "missing preferred family falls back",
+                vec![ipv4.clone()],
+                Some(AddressFamily::IPv6),
+                None,
+                Some("https://10.0.0.1:6443"),
+            ),
+            (
+                "not ready endpoints are skipped",
+                vec![not_ready],
+                None,
+                None,
+                Some("https://10.0.0.3:443"),
+            ),
+            (
+                "kubeadm endpoint is preferred",
+                vec![ipv4.clone()],
+                None,
+                Some("api.example.com:6443"),
+                Some("https://api.example.com:6443"),
+            ),
+            (
+                "kubeadm endpoint default port",
+                vec![],
+                None,
+                Some("api.example.com"),
+                Some("https://api.example.com:6443"),
+            ),
+            (
+                "kubeadm ipv6 endpoint",
+                vec![],
+                None,
+                Some("fd00::10"),
+                Some("https://[fd00::10]:6443"),
+            ),
+        ];
+
+        for (name, slices, family, kubeadm, expected) in cases {
+            assert_eq!(
+                expected.map(ToString::to_string),
+                FleetAddonConfig::infer_server_url(&slices, family, kubeadm),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_kubeadm_endpoint() {
+        use crate::api::fleet_addon_config::FleetAddonConfig;
+        use k8s_openapi::api::core::v1::ConfigMap;
+
+        let config_map = ConfigMap {
+            data: Some(
+                [(
+                    "ClusterConfiguration".to_string(),
+                    "apiVersion: kubeadm.k8s.io/v1beta3\ncontrolPlaneEndpoint: api.example.com:6443\n".to_string(),
+                )]
+                .into(),
+            ),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Some("api.example.com:6443".to_string()),
+            FleetAddonConfig::kubeadm_endpoint(&config_map)
+        );
+        assert_eq!(
+            None,
+            FleetAddonConfig::kubeadm_endpoint(&ConfigMap::default())
+        );
+    }
 }
@@ -0,0 +1,108 @@
+use base64::{Engine as _, prelude::BASE64_STANDARD};
+use x509_parser::pem::Pem;
+
+use super::{CertificateError, CertificateResult};
+
+const PEM_LINE_LENGTH: usize = 64;
+
+/// Builds a PEM bundle from the provided certificate sources.
+///
+/// Every source may contain multiple PEM blocks. Each certificate is validated
+/// to be a currently valid CA certificate. Duplicate certificates are skipped.
+pub(crate) fn ca_bundle(sources: &[String]) -> CertificateResult<String> {
+    let mut certificates: Vec<Vec<u8>> = vec![];
+
+    for source in sources {
+        for pem in Pem::iter_from_buffer(source.as_bytes()) {
+            let pem = pem.map_err(|e| CertificateError::Pem(e.to_string()))?;
+            if pem.label != "CERTIFICATE" {
+                return Err(CertificateError::UnexpectedBlock(pem.label));
+            }
+
+            validate(&pem)?;
+            if !certificates.contains(&pem.contents) {
+                certificates.push(pem.contents);
+            }
+        }
+    }
+
+    if certificates.is_empty() {
+        return Err(CertificateError::Empty);
+    }
+
+    Ok(certificates.iter().map(|der| encode(der)).collect())
+}
+
+fn validate(pem: &Pem) -> CertificateResult<()> {
+    let certificate = pem
+        .parse_x509()
+        .map_err(|e| CertificateError::Parse(e.to_string()))?;
+    let subject = certificate.subject().to_string();
+    let validity = certificate.validity();
+
+    if !validity.is_valid() {
+        return Err(CertificateError::Validity {
+            subject,
+            not_before: validity.not_before.to_string(),
+            not_after: validity.not_after.to_string(),
+        });
+    }
+
+    if !certificate.is_ca() {
+        return Err(CertificateError::NotCA { subject });
+    }
+
+    Ok(())
+}
+
+fn encode(der: &[u8]) -> String {
+    let encoded = BASE64_STANDARD.encode(der);
+    let body: Vec<&str> = encoded
+        .as_bytes()
+        .chunks(PEM_LINE_LENGTH)
+        .filter_map(|line| std::str::from_utf8(line).ok())
+        .collect();
+
+    format!(
+        "-----BEGIN CERTIFICATE-----\n{}\n-----END CERTIFICATE-----\n",
+        body.join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controllers::CertificateError;
+
+    use super::ca_bundle;
+
+    const CA: &str = include_str!("../../testdata/certs/ca.pem");
+    const CA_2: &str = include_str!("../../testdata/certs/ca-2.pem");
+    const EXPIRED_CA: &str = include_str!("../../testdata/certs/expired-ca.pem");
+    const LEAF: &str = include_str!("../../testdata/certs/leaf.pem");
+
+    #[test]
+    fn test_ca_bundle() {
+        assert_eq!(CA, ca_bundle(&[CA.into()]).unwrap());
+        assert_eq!(
+            format!("{CA}{CA_2}"),
+            ca_bundle(&[format!("{CA}{CA_2}"), CA.into()]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_ca_bundle_invalid() {
+        assert!(matches!(ca_bundle(&[]), Err(CertificateError::Empty)));
+        assert!(matches!(
+            ca_bundle(&["not a certificate".into()]),
+            Err(CertificateError::Empty)
+        ));
+        assert!(matches!(
+            ca_bundle(&[EXPIRED_CA.into()]),
+            Err(CertificateError::Validity { .. })
+        ));
+        assert!(matches!(
+            ca_bundle(&[CA.into(), LEAF.into()]),
+            Err(CertificateError::NotCA { .. })
+        ));
+    }
+}
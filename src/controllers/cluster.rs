@@ -1,5 +1,8 @@
-use crate::api::bundle_namespace_mapping::BundleNamespaceMapping;
-use crate::api::capi_cluster::{Cluster, FLEET_WORKSPACE_ANNOTATION};
+use crate::api::bundle_namespace_mapping::{BundleNamespaceMapping, to_bundle_selector};
+use crate::api::capi_cluster::{
+    CLUSTER_SOURCE_ANNOTATION, CLUSTER_SOURCE_UID_LABEL, Cluster, FLEET_WORKSPACE_ANNOTATION,
+};
+use crate::api::capi_clusterclass::ClusterClass;
 
 use crate::api::fleet_addon_config::FleetAddonConfig;
 use crate::api::fleet_cluster::{self};
@@ -10,19 +13,23 @@ use crate::api::fleet_clustergroup::ClusterGroup;
 use crate::controllers::addon_config::to_dynamic_event;
 use crate::controllers::controller::GetApi;
 use futures::StreamExt as _;
-use k8s_openapi::api::core::v1::Namespace;
+use jiff::Timestamp;
+use k8s_openapi::api::core::v1::{Namespace, Secret};
 use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
 use kube::api::{
-    ApiResource, DeleteParams, DynamicObject, GroupVersionKind, ListParams, PatchParams,
+    ApiResource, DynamicObject, GroupVersionKind, ListParams, ObjectMeta, PartialObjectMeta,
+    PatchParams,
 };
 
-use kube::client::scope;
+use kube::runtime::events::{Event, EventType};
+use kube::runtime::reflector::ObjectRef;
 use kube::runtime::watcher::{self, Config};
 use kube::{Api, Client};
 use kube::{
     Resource,
     api::{Patch, ResourceExt},
-    runtime::controller::Action
+    runtime::controller::Action,
 };
 use serde::Serialize;
 use serde_json::{Value, json};
@@ -30,14 +37,49 @@ use tracing::{debug, info};
 
 use std::sync::Arc;
 
+use super::conditions;
 use super::controller::{
-    Context, FleetBundle, FleetController, fetch_config, get_or_create, patch,
+    Context, FleetBundle, FleetController, delete, fetch_config, get_or_create, patch,
+};
+use super::dry_run::DryRunOperation;
+use super::{
+    BundleError, BundleMappingError, BundleResult, ClusterSyncError, ClusterSyncResult,
+    LabelCheckError,
 };
-use super::{BundleResult, ClusterSyncError, ClusterSyncResult};
 
 pub static CONTROLPLANE_INITIALIZED_CONDITION: &str = "ControlPlaneInitialized";
+pub static FLEET_NAME_AVAILABLE_CONDITION: &str = "FleetClusterNameAvailable";
+pub static FLEET_IMPORTED_CONDITION: &str = "FleetImported";
+
+/// Reason of the `FleetImported` condition.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ImportReason {
+    WaitingForControlPlane,
+    KubeconfigMissing,
+    Imported,
+    PatchFailed,
+}
+
+impl ImportReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            ImportReason::WaitingForControlPlane => "WaitingForControlPlane",
+            ImportReason::KubeconfigMissing => "KubeconfigMissing",
+            ImportReason::Imported => "Imported",
+            ImportReason::PatchFailed => "PatchFailed",
+        }
+    }
+
+    fn event_type(self) -> EventType {
+        match self {
+            ImportReason::WaitingForControlPlane | ImportReason::Imported => EventType::Normal,
+            ImportReason::KubeconfigMissing | ImportReason::PatchFailed => EventType::Warning,
+        }
+    }
+}
 
 pub struct FleetClusterBundle {
+    cluster: Cluster,
     namespace: Namespace,
     template_sources: TemplateSources,
     fleet: fleet_cluster::Cluster,
@@ -46,6 +88,7 @@ pub struct FleetClusterBundle {
     #[cfg(feature = "agent-initiated")]
     cluster_registration_token: Option<ClusterRegistrationToken>,
     config: FleetAddonConfig,
+    workspace: Option<String>,
 }
 
 pub struct TemplateSources(Cluster);
@@ -69,7 +112,10 @@ impl TemplateSources {
         // We need to remove all dynamic or unnessesary values from these resources
         let mut cluster = self.0.clone();
 
-        debug!("Mapping template values for Cluster: {}", cluster.metadata.name.clone()?);
+        debug!(
+            "Mapping template values for Cluster: {}",
+            cluster.metadata.name.clone()?
+        );
 
         cluster.status = None;
         cluster.meta_mut().managed_fields = None;
@@ -77,15 +123,22 @@ impl TemplateSources {
 
         // Get the ControlPlaneReference
         let reference = self.0.spec.proxy.control_plane_ref.as_ref()?;
-        
+
         // Get the API version from CRD.
-        // Note: This assumes the storage version is the right one. 
-        let crd_name = format!("{}.{}", to_plural(&reference.kind).to_lowercase(), reference.api_group);
+        // Note: This assumes the storage version is the right one.
+        let crd_name = format!(
+            "{}.{}",
+            to_plural(&reference.kind).to_lowercase(),
+            reference.api_group
+        );
         let crds: Api<CustomResourceDefinition> = Api::all(client.clone());
-        let crd: CustomResourceDefinition = crds.get(&crd_name).await.ok()?; 
+        let crd: CustomResourceDefinition = crds.get(&crd_name).await.ok()?;
         let api_version = crd.spec.versions.iter().find(|&version| version.storage)?;
 
-        debug!("Fetching ControlPlane resource: {} - {}/{}", reference.kind, &reference.api_group, &api_version.name);
+        debug!(
+            "Fetching ControlPlane resource: {} - {}/{}",
+            reference.kind, &reference.api_group, &api_version.name
+        );
 
         let resource = ApiResource::from_gvk(&GroupVersionKind::gvk(
             &reference.api_group,
@@ -99,7 +152,11 @@ impl TemplateSources {
         );
         let mut control_plane = api.get(&reference.name).await.ok()?;
 
-        debug!("Found {} object: {}", &control_plane.types.clone()?.kind, &control_plane.metadata.name.clone()?);
+        debug!(
+            "Found {} object: {}",
+            &control_plane.types.clone()?.kind,
+            &control_plane.metadata.name.clone()?
+        );
 
         if let Some(data_object) = control_plane.data.as_object_mut() {
             data_object.remove("status");
@@ -111,13 +168,20 @@ impl TemplateSources {
         let infra_reference = self.0.spec.proxy.infrastructure_ref.as_ref()?;
 
         // Get the API version from CRD.
-        // Note: This assumes the storage version is the right one. 
-        let crd_name = format!("{}.{}", to_plural(&infra_reference.kind).to_lowercase(), infra_reference.api_group);
+        // Note: This assumes the storage version is the right one.
+        let crd_name = format!(
+            "{}.{}",
+            to_plural(&infra_reference.kind).to_lowercase(),
+            infra_reference.api_group
+        );
         let crds: Api<CustomResourceDefinition> = Api::all(client.clone());
-        let crd: CustomResourceDefinition = crds.get(&crd_name).await.ok()?; 
+        let crd: CustomResourceDefinition = crds.get(&crd_name).await.ok()?;
         let api_version = crd.spec.versions.iter().find(|&version| version.storage)?;
 
-        debug!("Fetching InfrastructureCluster resource: {} - {}/{}", &infra_reference.kind, &infra_reference.api_group, &api_version.name);
+        debug!(
+            "Fetching InfrastructureCluster resource: {} - {}/{}",
+            &infra_reference.kind, &infra_reference.api_group, &api_version.name
+        );
 
         let resource = ApiResource::from_gvk(&GroupVersionKind::gvk(
             &infra_reference.api_group,
@@ -131,7 +195,11 @@ impl TemplateSources {
         );
         let mut infrastructure_cluster = api.get(&infra_reference.name).await.ok()?;
 
-        debug!("Found {} object: {}", &infrastructure_cluster.types.clone()?.kind, &infrastructure_cluster.metadata.name.clone()?);
+        debug!(
+            "Found {} object: {}",
+            &infrastructure_cluster.types.clone()?.kind,
+            &infrastructure_cluster.metadata.name.clone()?
+        );
 
         if let Some(data_object) = infrastructure_cluster.data.as_object_mut() {
             data_object.remove("status");
@@ -152,6 +220,172 @@ impl TemplateSources {
 impl FleetBundle for FleetClusterBundle {
     #[allow(refining_impl_trait)]
     async fn sync(&mut self, ctx: Arc<Context>) -> ClusterSyncResult<Action> {
+        let result = self.import(ctx.clone()).await;
+        let (reason, message) = match &result {
+            // Reported by the name availability condition
+            Err(ClusterSyncError::NameCollision { .. }) => return result,
+            Err(e) => (
+                ImportReason::PatchFailed,
+                format!("Fleet cluster import failed: {e}"),
+            ),
+            Ok(_) if !self.kubeconfig_available(ctx.clone()).await? => {
+                ctx.imports.kubeconfig_missing(&self.cluster);
+                (
+                    ImportReason::KubeconfigMissing,
+                    format!(
+                        "Kubeconfig secret `{}-kubeconfig` is missing",
+                        self.cluster.name_any()
+                    ),
+                )
+            }
+            Ok(_) => (
+                ImportReason::Imported,
+                format!(
+                    "Imported as Fleet cluster `{}/{}`",
+                    self.fleet.namespace().unwrap_or_default(),
+                    self.fleet.name_any()
+                ),
+            ),
+        };
+
+        self.cluster
+            .set_import_condition(ctx, reason, message)
+            .await
+            .map_err(ClusterSyncError::ConditionError)?;
+
+        result
+    }
+
+    async fn cleanup(&mut self, ctx: Arc<Context>) -> Result<Action, super::SyncError> {
+        if let Some(mapping) = self.mapping.as_ref() {
+            let ns = mapping.namespace().unwrap_or_default();
+            let other_clusters = ctx.clusters_by_class_namespace(&ns).await?;
+
+            let bundle_namespace_mapping =
+                BundleNamespaceMapping::get_api(ctx.client.clone(), mapping.get_namespace());
+
+            if !self.mapping_referenced(&other_clusters)
+                && let Some(mapping) = bundle_namespace_mapping
+                    .get_opt(&mapping.name_any())
+                    .await?
+            {
+                delete(ctx.clone(), &bundle_namespace_mapping, &mapping).await?;
+            }
+        }
+
+        self.remove_stale_imports(ctx.clone()).await?;
+
+        if self.workspace.is_some() {
+            // Objects in a shared workspace have no owner references, so they are removed explicitly.
+            self.cleanup_workspace(ctx.clone()).await?;
+            return Ok(Action::await_change());
+        }
+
+        // Find all other clusters in this namespace
+        let other_clusters = ctx
+            .clusters_in_namespace(self.cluster.get_namespace())
+            .await?;
+        // If no other clusters are found in this namespace, remove the fleet workspace annotation.
+        if other_clusters
+            .iter()
+            .all(|c| c.name_any() == self.cluster.name_any())
+        {
+            let patch = json!({
+                "metadata": {
+                    "annotations": {
+                        FLEET_WORKSPACE_ANNOTATION: null
+                    }
+                }
+            });
+            let namespaces = Namespace::get_api(ctx.client.clone(), &());
+            if ctx.flags.dry_run {
+                let live = namespaces.get(self.cluster.get_namespace()).await?;
+                ctx.dry_run
+                    .merge(&live, DryRunOperation::Annotations, &patch)
+                    .await;
+                return Ok(Action::await_change());
+            }
+
+            namespaces
+                .patch_metadata(
+                    self.cluster.get_namespace(),
+                    &PatchParams::default(),
+                    &Patch::Merge(&patch),
+                )
+                .await?;
+            debug!(
+                "Removed fleet annotation from namespace {}.",
+                self.cluster.get_namespace()
+            );
+        }
+
+        Ok(Action::await_change())
+    }
+}
+
+impl FleetClusterBundle {
+    /// Creates or updates the Fleet cluster and the related resources.
+    async fn import(&mut self, ctx: Arc<Context>) -> ClusterSyncResult<Action> {
+        // A Fleet cluster generated for another Cluster is never adopted, the collision is reported instead
+        if let Some(source_cluster) = self.colliding_source(ctx.clone()).await? {
+            let name = self.fleet.name_any();
+            self.cluster
+                .set_condition(
+                    ctx.clone(),
+                    Condition {
+                        last_transition_time: Time(Timestamp::now()),
+                        message: format!(
+                            "Fleet cluster name `{name}` is already used by cluster `{source_cluster}`"
+                        ),
+                        observed_generation: None,
+                        reason: "NameCollision".into(),
+                        status: "False".into(),
+                        type_: FLEET_NAME_AVAILABLE_CONDITION.into(),
+                    },
+                )
+                .await
+                .map_err(ClusterSyncError::ConditionError)?;
+
+            self.cluster
+                .publish_event(
+                    ctx.clone(),
+                    EventType::Warning,
+                    "NameCollision",
+                    format!(
+                        "Fleet cluster name `{name}` is already used by cluster `{source_cluster}`"
+                    ),
+                )
+                .await
+                .map_err(ClusterSyncError::Event)?;
+
+            return Err(ClusterSyncError::NameCollision {
+                name,
+                source_cluster,
+            });
+        }
+
+        self.cluster
+            .set_condition_if_present(
+                ctx.clone(),
+                Condition {
+                    last_transition_time: Time(Timestamp::now()),
+                    message: format!(
+                        "Fleet cluster name `{}` is available",
+                        self.fleet.name_any()
+                    ),
+                    observed_generation: None,
+                    reason: "NameAvailable".into(),
+                    status: "True".into(),
+                    type_: FLEET_NAME_AVAILABLE_CONDITION.into(),
+                },
+            )
+            .await
+            .map_err(ClusterSyncError::ConditionError)?;
+
+        if self.workspace.is_some() {
+            self.sync_kubeconfig(ctx.clone()).await?;
+        }
+
         let cluster = &mut self.fleet;
 
         if let Some(template) = self.template_sources.resolve(ctx.client.clone()).await {
@@ -159,23 +393,23 @@ impl FleetBundle for FleetClusterBundle {
             cluster.spec.template_values = Some(template);
         }
 
-        if let Some(mapping) = self.mapping.as_mut() {
-            if self.config.cluster_patch_enabled() {
-                let cluster_name = cluster.name_any();
-                patch(
-                    ctx.clone(),
-                    mapping,
-                    &PatchParams::apply(&format!("cluster-{cluster_name}-addon-provider-fleet")),
-                )
-                .await
-                .map_err(ClusterSyncError::BundleNamespaceMappingError)?;
+        if let Some(mapping) = self.mapping.as_mut()
+            && self.config.cluster_patch_enabled()
+        {
+            let cluster_name = cluster.name_any();
+            patch(
+                ctx.clone(),
+                mapping,
+                &PatchParams::apply(&format!("cluster-{cluster_name}-addon-provider-fleet")),
+            )
+            .await
+            .map_err(ClusterSyncError::BundleNamespaceMappingError)?;
 
-                let class_namespace = mapping.namespace().unwrap_or_default();
-                let cluster_namespace = mapping.name_any();
-                info!(
-                    "Updated BundleNamespaceMapping for cluster {cluster_name} between class namespace: {class_namespace} and cluster namespace: {cluster_namespace}"
-                );
-            }
+            let class_namespace = mapping.namespace().unwrap_or_default();
+            let cluster_namespace = mapping.name_any();
+            info!(
+                "Updated BundleNamespaceMapping for cluster {cluster_name} between class namespace: {class_namespace} and cluster namespace: {cluster_namespace}"
+            );
         }
 
         if self.config.cluster_patch_enabled() {
@@ -221,68 +455,320 @@ impl FleetBundle for FleetClusterBundle {
             self.fleet.get_namespace()
         );
 
+        self.remove_stale_imports(ctx.clone())
+            .await
+            .map_err(ClusterSyncError::StaleImportRemoval)?;
+
         Ok(Action::await_change())
     }
 
-    async fn cleanup(&mut self, ctx: Arc<Context>) -> Result<Action, super::SyncError> {
-        if let Some(mapping) = self.mapping.as_ref() {
-            let ns = mapping.namespace();
-            let other_clusters = ctx
-                .client
-                .list::<Cluster>(
-                    &ListParams::default(),
-                    &scope::Namespace::from(ns.clone().unwrap_or_default()),
-                )
-                .await?;
+    /// Checks if the CAPI kubeconfig secret used by the Fleet cluster exists.
+    async fn kubeconfig_available(&self, ctx: Arc<Context>) -> ClusterSyncResult<bool> {
+        if self.fleet.spec.kube_config_secret.is_none() {
+            return Ok(true);
+        }
 
-            let referencing_cluster = other_clusters.iter().find(|c| {
-                c.cluster_class_namespace() == ns
-                    && c.name_any() != self.fleet.name_any()
-                    && c.metadata.deletion_timestamp.is_none()
-            });
+        let kubeconfig = Secret::get_api(ctx.client.clone(), self.cluster.get_namespace())
+            .get_metadata_opt(&format!("{}-kubeconfig", self.cluster.name_any()))
+            .await
+            .map_err(ClusterSyncError::KubeconfigLookupError)?;
 
-            if referencing_cluster.is_some() {
-                return Ok(Action::await_change());
+        Ok(kubeconfig.is_some())
+    }
+
+    /// Moves generated Fleet objects into a shared Fleet workspace namespace.
+    ///
+    /// Owner references are dropped, as they can't point to the Cluster across namespaces.
+    fn into_workspace(mut self, workspace: String) -> Self {
+        self.fleet.metadata.namespace = Some(workspace.clone());
+        self.fleet.metadata.owner_references = None;
+        if self.fleet.spec.kube_config_secret.is_some() {
+            self.fleet.spec.kube_config_secret = Some(kubeconfig_replica_name(&self.fleet));
+        }
+
+        if let Some(group) = self.fleet_group.as_mut() {
+            group.metadata.namespace = Some(workspace.clone());
+            group.metadata.owner_references = None;
+        }
+
+        if let Some(mapping) = self.mapping.as_mut() {
+            mapping.metadata.name = Some(workspace.clone());
+            mapping.namespace_selector.match_labels =
+                Some([("kubernetes.io/metadata.name".to_string(), workspace.clone())].into());
+        }
+
+        #[cfg(feature = "agent-initiated")]
+        if let Some(token) = self.cluster_registration_token.as_mut() {
+            token.metadata.namespace = Some(workspace.clone());
+        }
+
+        self.namespace.metadata.name = Some(workspace.clone());
+        self.workspace = Some(workspace);
+        self
+    }
+
+    /// Replicates the Cluster kubeconfig secret into the Fleet workspace namespace.
+    async fn sync_kubeconfig(&self, ctx: Arc<Context>) -> ClusterSyncResult<()> {
+        let Some(name) = self.fleet.spec.kube_config_secret.clone() else {
+            return Ok(());
+        };
+
+        let source = Secret::get_api(ctx.client.clone(), self.cluster.get_namespace())
+            .get_opt(&format!("{}-kubeconfig", self.cluster.name_any()))
+            .await
+            .map_err(ClusterSyncError::KubeconfigLookupError)?;
+
+        let Some(source) = source else {
+            debug!("Kubeconfig secret is not yet available. Nothing to replicate.");
+            return Ok(());
+        };
+
+        let mut replica = Secret {
+            metadata: ObjectMeta {
+                name: Some(name),
+                namespace: self.fleet.namespace(),
+                annotations: Some(
+                    [(CLUSTER_SOURCE_ANNOTATION.to_string(), self.cluster.source())].into(),
+                ),
+                ..Default::default()
+            },
+            data: source.data,
+            type_: source.type_,
+            ..Default::default()
+        };
+
+        patch(
+            ctx,
+            &mut replica,
+            &PatchParams::apply("addon-provider-fleet").force(),
+        )
+        .await
+        .map_err(ClusterSyncError::KubeconfigPatchError)?;
+
+        Ok(())
+    }
+
+    /// Removes the Fleet cluster and the kubeconfig replica from the Fleet workspace namespace.
+    async fn cleanup_workspace(&self, ctx: Arc<Context>) -> Result<(), kube::Error> {
+        self.remove_import(ctx, self.fleet.get_namespace(), &self.fleet.name_any())
+            .await?;
+
+        debug!(
+            "Removed Fleet cluster from workspace {}.",
+            self.fleet.get_namespace()
+        );
+
+        Ok(())
+    }
+
+    /// Removes the Fleet clusters imported for this Cluster into a previous location,
+    /// left behind when the Cluster namespace was mapped to a different workspace.
+    async fn remove_stale_imports(&self, ctx: Arc<Context>) -> Result<(), kube::Error> {
+        let Some(uid) = self.cluster.uid() else {
+            return Ok(());
+        };
+
+        // Served from the Fleet cluster store, as the lookup runs on every import
+        for fleet in ctx.fleet_clusters().await? {
+            let (namespace, name) = (fleet.namespace().unwrap_or_default(), fleet.name_any());
+            let labeled = fleet.labels().get(CLUSTER_SOURCE_UID_LABEL) == Some(&uid);
+            // Fleet clusters imported into the Cluster namespace before it was mapped may predate the label
+            let legacy = self.workspace.is_some()
+                && namespace == *self.cluster.get_namespace()
+                && name == self.fleet.name_any();
+            if !(labeled || legacy)
+                || (namespace == *self.fleet.get_namespace() && name == self.fleet.name_any())
+                || !self.generated(fleet.as_ref())
+            {
+                continue;
             }
 
-            let bundle_namespace_mapping = BundleNamespaceMapping::get_api(ctx.client.clone(), mapping.get_namespace());
+            self.remove_import(ctx.clone(), &namespace, &name).await?;
+            info!("Removed Fleet cluster {name} from the previous workspace {namespace}");
+        }
 
-            if bundle_namespace_mapping.get_opt(&mapping.name_any()).await?.is_some() {
-                bundle_namespace_mapping.delete(&mapping.name_any(), &DeleteParams::default()).await?;
+        Ok(())
+    }
+
+    /// Removes a Fleet cluster generated for this Cluster, with its kubeconfig replica
+    /// and the class group once it has no other members.
+    async fn remove_import(
+        &self,
+        ctx: Arc<Context>,
+        namespace: &str,
+        name: &str,
+    ) -> Result<(), kube::Error> {
+        let fleet_clusters = fleet_cluster::Cluster::get_api(ctx.client.clone(), namespace);
+        if let Some(fleet) = fleet_clusters.get_metadata_opt(name).await?
+            && self.generated(&fleet)
+        {
+            delete(ctx.clone(), &fleet_clusters, &fleet).await?;
+        }
+
+        // Kubeconfig secrets are only replicated into workspaces
+        if namespace != self.cluster.get_namespace() {
+            let replica = format!("{name}-kubeconfig");
+            let secrets = Secret::get_api(ctx.client.clone(), namespace);
+            if let Some(secret) = secrets.get_metadata_opt(&replica).await?
+                && self.generated(&secret)
+            {
+                delete(ctx.clone(), &secrets, &secret).await?;
             }
         }
 
-        // List all other clusters in this namespace
-        let other_clusters = Cluster::get_api(ctx.client.clone(), self.fleet.get_namespace())
-            .list(
-                &ListParams::default()
-                    .fields(&format!("metadata.name!={}", self.fleet.name_any()))
-                    .limit(1),
-            )
+        self.remove_unused_group(ctx, namespace).await
+    }
+
+    /// Removes the class group from the namespace once no Fleet cluster of another Cluster is a member.
+    async fn remove_unused_group(
+        &self,
+        ctx: Arc<Context>,
+        namespace: &str,
+    ) -> Result<(), kube::Error> {
+        let Some(group) = self.fleet_group.as_ref() else {
+            return Ok(());
+        };
+
+        let Some(selector) = group
+            .spec
+            .selector
+            .as_ref()
+            .and_then(|selector| selector.match_labels.as_ref())
+            .filter(|labels| !labels.is_empty())
+            .map(|labels| {
+                labels
+                    .iter()
+                    .map(|(key, value)| format!("{key}={value}"))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+        else {
+            return Ok(());
+        };
+
+        let members = fleet_cluster::Cluster::get_api(ctx.client.clone(), namespace)
+            .list_metadata(&ListParams::default().labels(&selector))
             .await?;
-        // If no other clusters are found in this namespace, remove the fleet workspace annotation.
-        if other_clusters.items.is_empty() {
-            let patch = json!({
-                "metadata": {
-                    "annotations": {
-                        FLEET_WORKSPACE_ANNOTATION: null
-                    }
-                }
-            });
-            Namespace::get_api(ctx.client.clone(), &())
-                .patch_metadata(
-                    self.fleet.get_namespace(),
-                    &PatchParams::default(),
-                    &Patch::Merge(&patch),
-                )
-                .await?;
+        if self.group_in_use(&members.items) {
+            return Ok(());
+        }
+
+        let groups = ClusterGroup::get_api(ctx.client.clone(), namespace);
+        if let Some(live) = groups.get_metadata_opt(&group.name_any()).await? {
+            delete(ctx.clone(), &groups, &live).await?;
             debug!(
-                "Removed fleet annotation from namespace {}.",
-                self.fleet.get_namespace()
+                "Removed unused ClusterGroup {} from {namespace}.",
+                group.name_any()
             );
         }
 
-        Ok(Action::await_change())
+        Ok(())
+    }
+
+    /// Checks if the object was generated for this Cluster, by the source annotation or the owner reference.
+    fn generated(&self, obj: &impl ResourceExt) -> bool {
+        let uid = self.cluster.uid();
+        obj.annotations().get(CLUSTER_SOURCE_ANNOTATION) == Some(&self.cluster.source())
+            || obj
+                .owner_references()
+                .iter()
+                .any(|owner| owner.kind == Cluster::kind(&()) && Some(&owner.uid) == uid.as_ref())
+    }
+
+    /// Checks if another live Cluster still generates the `BundleNamespaceMapping`.
+    ///
+    /// Mappings in a shared workspace are referenced by clusters from any mapped namespace.
+    fn mapping_referenced(&self, clusters: &[Arc<Cluster>]) -> bool {
+        clusters.iter().any(|c| {
+            (self.workspace.is_some() || c.namespace() == self.cluster.namespace())
+                && c.uid() != self.cluster.uid()
+                && c.metadata.deletion_timestamp.is_none()
+        })
+    }
+
+    /// Checks if a live Fleet cluster generated for another Cluster is a member of the class group.
+    fn group_in_use(&self, members: &[PartialObjectMeta<fleet_cluster::Cluster>]) -> bool {
+        members
+            .iter()
+            .any(|member| member.metadata.deletion_timestamp.is_none() && !self.generated(member))
+    }
+
+    /// Keeps the name of a Fleet cluster imported for this Cluster before long names were truncated,
+    /// instead of orphaning it by importing the Cluster under the truncated name.
+    async fn keep_legacy_name(&mut self, ctx: Arc<Context>, name: String) -> kube::Result<()> {
+        if name == self.fleet.name_any() {
+            return Ok(());
+        }
+
+        let Some(existing) =
+            fleet_cluster::Cluster::get_api(ctx.client.clone(), self.fleet.get_namespace())
+                .get_metadata_opt(&name)
+                .await?
+        else {
+            return Ok(());
+        };
+
+        if self.generated(&existing) {
+            info!(
+                "Keeping the existing Fleet cluster name `{name}` instead of `{}`",
+                self.fleet.name_any()
+            );
+            self.fleet.metadata.name = Some(name);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the source of an existing Fleet cluster with the same name, if it was not generated for this Cluster.
+    ///
+    /// Fleet clusters without a source are adopted, which is reported with an event.
+    async fn colliding_source(&self, ctx: Arc<Context>) -> ClusterSyncResult<Option<String>> {
+        let existing =
+            fleet_cluster::Cluster::get_api(ctx.client.clone(), self.fleet.get_namespace())
+                .get_metadata_opt(&self.fleet.name_any())
+                .await
+                .map_err(ClusterSyncError::NameLookupError)?;
+
+        let Some(existing) = existing else {
+            return Ok(None);
+        };
+
+        let source = self.cluster.source();
+        if let Some(existing_source) = existing.annotations().get(CLUSTER_SOURCE_ANNOTATION) {
+            return Ok((*existing_source != source).then(|| existing_source.clone()));
+        }
+
+        // Fleet clusters created before the source annotation was introduced are matched by owner.
+        let uid = self.cluster.uid();
+        let mut owners = existing
+            .owner_references()
+            .iter()
+            .filter(|owner| owner.kind == Cluster::kind(&()));
+        if let Some(owner) = owners
+            .clone()
+            .find(|owner| Some(&owner.uid) != uid.as_ref())
+        {
+            return Ok(Some(format!(
+                "{}/{}",
+                self.cluster.namespace().unwrap_or_default(),
+                owner.name
+            )));
+        }
+
+        if owners.next().is_none() {
+            let note = format!(
+                "Adopting existing Fleet cluster `{}/{}` without an owner",
+                existing.namespace().unwrap_or_default(),
+                existing.name_any()
+            );
+            info!("{note}");
+            self.cluster
+                .publish_event(ctx, EventType::Normal, "Adopted", note)
+                .await
+                .map_err(ClusterSyncError::Event)?;
+        }
+
+        Ok(None)
     }
 }
 
@@ -296,34 +782,251 @@ impl FleetController for Cluster {
             return Ok(None);
         }
 
-        if self.cluster_ready().is_none_or(|initialized| !initialized){
+        if self.cluster_ready().is_none_or(|initialized| !initialized) {
             debug!("ControlPlane not yet initialized. Nothing to do.");
+            if self.metadata.deletion_timestamp.is_none() {
+                self.set_import_condition(
+                    ctx,
+                    ImportReason::WaitingForControlPlane,
+                    "Waiting for the control plane to be initialized".into(),
+                )
+                .await
+                .map_err(BundleError::Condition)?;
+            }
             return Ok(None);
         }
 
-        Ok(Some(FleetClusterBundle {
+        let cluster_config = config.spec.cluster.clone().unwrap_or_default();
+        let workspace = if cluster_config.workspace_mapping_enabled() {
+            let namespace = Namespace::get_api(ctx.client.clone(), &())
+                .get(self.get_namespace())
+                .await
+                .map_err(LabelCheckError::NamespaceLookup)?;
+            cluster_config
+                .workspace(&namespace)
+                .map_err(LabelCheckError::Expression)?
+        } else {
+            None
+        };
+
+        let mut fleet = self.to_cluster(config.spec.cluster.as_ref());
+        if config.cluster_class_version_groups_enabled() {
+            fleet.labels_mut().extend(self.class_version_labels());
+        }
+
+        let legacy_name = cluster_config.untruncated_naming(self);
+        let mut bundle = FleetClusterBundle {
+            cluster: self.clone(),
             template_sources: TemplateSources::new(self),
-            fleet: self.to_cluster(config.spec.cluster.as_ref()),
+            fleet,
             fleet_group: self.to_group(config.spec.cluster.as_ref()),
-            mapping: self.to_bundle_ns_mapping(config.spec.cluster.as_ref()),
+            mapping: self.class_bundle_ns_mapping(ctx.clone(), &config).await?,
             #[cfg(feature = "agent-initiated")]
             cluster_registration_token: self
                 .to_cluster_registration_token(config.spec.cluster.as_ref()),
             config,
             namespace: self.to_namespace(),
+            workspace: None,
+        };
+
+        Ok(Some(match workspace {
+            Some(workspace) => bundle.into_workspace(workspace),
+            None => {
+                bundle
+                    .keep_legacy_name(ctx, legacy_name)
+                    .await
+                    .map_err(BundleError::NameLookup)?;
+                bundle
+            }
         }))
     }
 }
 
 impl Cluster {
+    /// Builds the `BundleNamespaceMapping`, preferring the bundle selector from the `ClusterClass` annotation.
+    async fn class_bundle_ns_mapping(
+        &self,
+        ctx: Arc<Context>,
+        config: &FleetAddonConfig,
+    ) -> Result<Option<BundleNamespaceMapping>, BundleMappingError> {
+        let Some(mut mapping) = self.to_bundle_ns_mapping(config.spec.cluster.as_ref()) else {
+            return Ok(None);
+        };
+
+        let Some(class_ref) = self
+            .spec
+            .proxy
+            .topology
+            .as_ref()
+            .map(|topology| &topology.class_ref)
+        else {
+            return Ok(Some(mapping));
+        };
+
+        // Read from the ClusterClass store shared with the ClusterClass controller
+        let class = ctx
+            .cluster_class(
+                &ObjectRef::<ClusterClass>::new(&class_ref.name)
+                    .within(&mapping.namespace().unwrap_or_default()),
+            )
+            .await?;
+        if let Some(selector) = class.bundle_selector()? {
+            mapping.bundle_selector = to_bundle_selector(&selector);
+        }
+
+        Ok(Some(mapping))
+    }
+
     #[must_use]
     pub fn cluster_ready(&self) -> Option<bool> {
         let status = self.status.clone()?;
 
         match status.initialization {
             Some(initialization) => initialization.control_plane_initialized,
-            None => None
+            None => None,
+        }
+    }
+
+    /// Sets a condition in the Cluster `status.conditions`, owned by the addon provider.
+    ///
+    /// The status is only patched when the condition differs from the observed one.
+    pub(crate) async fn set_condition(
+        &self,
+        ctx: Arc<Context>,
+        mut condition: Condition,
+    ) -> kube::Result<()> {
+        let existing = self
+            .status
+            .as_ref()
+            .and_then(|status| status.conditions.as_ref())
+            .and_then(|conditions| conditions.iter().find(|c| c.type_ == condition.type_));
+
+        condition.observed_generation = self.metadata.generation;
+        let Some(condition) = conditions::merge(existing, condition) else {
+            return Ok(());
+        };
+        if ctx.flags.dry_run {
+            return Ok(());
+        }
+
+        let (params, patch) = Self::condition_patch(&condition);
+        Cluster::get_api(ctx.client.clone(), self.get_namespace())
+            .patch_status(&self.name_any(), &params, &Patch::Apply(&patch))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Server-side apply of a single condition, by the field manager of the condition type.
+    pub(crate) fn condition_patch(condition: &Condition) -> (PatchParams, Value) {
+        let params = PatchParams::apply(&conditions::field_manager(&condition.type_)).force();
+        let patch = json!({
+            "apiVersion": Cluster::api_version(&()),
+            "kind": Cluster::kind(&()),
+            "status": {
+                "conditions": [condition],
+            },
+        });
+
+        (params, patch)
+    }
+
+    /// Sets the `FleetImported` condition, recording an event when the reason changes.
+    pub(crate) async fn set_import_condition(
+        &self,
+        ctx: Arc<Context>,
+        reason: ImportReason,
+        message: String,
+    ) -> kube::Result<()> {
+        let changed = self
+            .status
+            .as_ref()
+            .and_then(|status| status.conditions.as_ref())
+            .and_then(|conditions| {
+                conditions
+                    .iter()
+                    .find(|c| c.type_ == FLEET_IMPORTED_CONDITION)
+            })
+            .is_none_or(|c| c.reason != reason.as_str());
+
+        self.set_condition(
+            ctx.clone(),
+            Condition {
+                last_transition_time: Time(Timestamp::now()),
+                message: message.clone(),
+                observed_generation: None,
+                reason: reason.as_str().into(),
+                status: if reason == ImportReason::Imported {
+                    "True"
+                } else {
+                    "False"
+                }
+                .into(),
+                type_: FLEET_IMPORTED_CONDITION.into(),
+            },
+        )
+        .await?;
+
+        if !changed {
+            return Ok(());
         }
+
+        self.publish_event(ctx, reason.event_type(), reason.as_str(), message)
+            .await
+    }
+
+    /// Publishes an import event on the Cluster, skipped in the dry-run mode.
+    pub(crate) async fn publish_event(
+        &self,
+        ctx: Arc<Context>,
+        type_: EventType,
+        reason: &str,
+        note: String,
+    ) -> kube::Result<()> {
+        if ctx.flags.dry_run {
+            return Ok(());
+        }
+
+        match ctx
+            .diagnostics
+            .read()
+            .await
+            .recorder(ctx.client.clone())
+            .publish(
+                &Event {
+                    type_,
+                    reason: reason.into(),
+                    note: Some(note),
+                    action: "Importing".into(),
+                    secondary: None,
+                },
+                &self.object_ref(&()),
+            )
+            .await
+        {
+            // Events can't be created while the Cluster namespace is terminating
+            Err(kube::Error::Api(e)) if e.is_forbidden() => Ok(()),
+            e => e,
+        }
+    }
+
+    /// Sets a condition only if a condition of the same type was previously set.
+    pub(crate) async fn set_condition_if_present(
+        &self,
+        ctx: Arc<Context>,
+        condition: Condition,
+    ) -> kube::Result<()> {
+        let present = self
+            .status
+            .as_ref()
+            .and_then(|status| status.conditions.as_ref())
+            .is_some_and(|conditions| conditions.iter().any(|c| c.type_ == condition.type_));
+
+        if present {
+            self.set_condition(ctx, condition).await?;
+        }
+
+        Ok(())
     }
 
     /// Adds a dynamic watcher for a specific namespace.
@@ -394,3 +1097,159 @@ fn to_plural(word: &str) -> String {
     // All other words will have "s" added to the end (eg. days).
     format!("{word}s")
 }
+
+/// Name of the kubeconfig secret replica for a Fleet cluster in a shared workspace.
+fn kubeconfig_replica_name(fleet: &fleet_cluster::Cluster) -> String {
+    format!("{}-kubeconfig", fleet.name_any())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
+    use kube::core::PartialObjectMetaExt as _;
+    use serde_json::json;
+
+    use crate::api::capi_cluster::Cluster;
+    use crate::api::fleet_addon_config::FleetAddonConfig;
+    use crate::api::fleet_cluster;
+
+    use super::{
+        FLEET_IMPORTED_CONDITION, FLEET_NAME_AVAILABLE_CONDITION, FleetClusterBundle,
+        TemplateSources,
+    };
+
+    fn cluster(namespace: &str, uid: &str) -> Cluster {
+        let mut cluster: Cluster = serde_yaml::from_str(
+            r"
+            apiVersion: cluster.x-k8s.io/v1beta2
+            kind: Cluster
+            metadata:
+              name: dev
+            spec:
+              topology:
+                version: v1.33.1
+                classRef:
+                  name: quick-start
+                  namespace: classes
+            ",
+        )
+        .unwrap();
+        cluster.metadata.namespace = Some(namespace.into());
+        cluster.metadata.uid = Some(uid.into());
+        cluster
+    }
+
+    fn bundle(cluster: &Cluster, config: &FleetAddonConfig) -> FleetClusterBundle {
+        let cluster_config = config.spec.cluster.as_ref();
+        FleetClusterBundle {
+            cluster: cluster.clone(),
+            namespace: cluster.to_namespace(),
+            template_sources: TemplateSources::new(cluster),
+            fleet: cluster.to_cluster(cluster_config),
+            fleet_group: cluster.to_group(cluster_config),
+            mapping: cluster.to_bundle_ns_mapping(cluster_config),
+            #[cfg(feature = "agent-initiated")]
+            cluster_registration_token: None,
+            config: config.clone(),
+            workspace: None,
+        }
+        .into_workspace("shared".into())
+    }
+
+    fn member(
+        fleet: &fleet_cluster::Cluster,
+    ) -> kube::api::PartialObjectMeta<fleet_cluster::Cluster> {
+        fleet.metadata.clone().into_response_partial()
+    }
+
+    #[test]
+    fn test_shared_workspace_cleanup() {
+        let config: FleetAddonConfig = serde_yaml::from_str(
+            r"
+            apiVersion: addons.cluster.x-k8s.io/v1alpha1
+            kind: FleetAddonConfig
+            metadata:
+              name: fleet-addon-config
+            spec:
+              cluster:
+                applyClassGroup: true
+                namespaceSelector: {}
+                selector: {}
+                naming:
+                  template: '{namespace}-{name}'
+                workspaceMappings:
+                - workspace: shared
+                  namespaces: [tenant-a, tenant-b]
+            ",
+        )
+        .unwrap();
+
+        let (a, b) = (cluster("tenant-a", "a"), cluster("tenant-b", "b"));
+        let (bundle_a, bundle_b) = (bundle(&a, &config), bundle(&b, &config));
+        assert_eq!(Some("shared"), bundle_a.fleet.metadata.namespace.as_deref());
+        assert!(bundle_a.generated(&bundle_a.fleet));
+        assert!(!bundle_a.generated(&bundle_b.fleet));
+
+        // Deleting the first cluster keeps the mapping and the group used by the second one
+        let mut deleting = a.clone();
+        deleting.metadata.deletion_timestamp = Some(Time(jiff::Timestamp::now()));
+        let clusters = [Arc::new(deleting), Arc::new(b.clone())];
+        assert!(bundle_a.mapping_referenced(&clusters));
+        let members = [member(&bundle_a.fleet), member(&bundle_b.fleet)];
+        assert!(bundle_a.group_in_use(&members));
+
+        // The mapping and the group are removed with the last cluster
+        assert!(!bundle_b.mapping_referenced(&clusters[..1]));
+        assert!(!bundle_b.group_in_use(&[member(&bundle_b.fleet)]));
+        let mut removed = bundle_b.fleet.clone();
+        removed.metadata.deletion_timestamp = Some(Time(jiff::Timestamp::now()));
+        assert!(!bundle_a.group_in_use(&[member(&bundle_a.fleet), member(&removed)]));
+    }
+
+    #[test]
+    fn test_condition_patch_field_managers() {
+        let condition = |type_: &str| Condition {
+            last_transition_time: Time(jiff::Timestamp::UNIX_EPOCH),
+            message: String::new(),
+            observed_generation: Some(1),
+            reason: "Reason".into(),
+            status: "True".into(),
+            type_: type_.into(),
+        };
+
+        let (imported_params, imported) =
+            Cluster::condition_patch(&condition(FLEET_IMPORTED_CONDITION));
+        let (name_params, name) =
+            Cluster::condition_patch(&condition(FLEET_NAME_AVAILABLE_CONDITION));
+
+        // Each condition is owned by its own manager, so an apply never drops the other one
+        assert_eq!(
+            Some("addon-provider-fleet-FleetImported"),
+            imported_params.field_manager.as_deref()
+        );
+        assert_eq!(
+            Some("addon-provider-fleet-FleetClusterNameAvailable"),
+            name_params.field_manager.as_deref()
+        );
+        assert!(imported_params.force && name_params.force);
+
+        assert_eq!(
+            json!([{
+                "lastTransitionTime": "1970-01-01T00:00:00Z",
+                "message": "",
+                "observedGeneration": 1,
+                "reason": "Reason",
+                "status": "True",
+                "type": "FleetImported",
+            }]),
+            imported["status"]["conditions"]
+        );
+        assert_eq!(
+            json!("FleetClusterNameAvailable"),
+            name["status"]["conditions"][0]["type"]
+        );
+        assert_eq!(json!("Cluster"), name["kind"]);
+    }
+}
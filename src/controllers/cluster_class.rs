@@ -1,20 +1,27 @@
 use crate::api::capi_clusterclass::ClusterClass;
 
 use crate::api::fleet_addon_config::{ClusterClassConfig, FleetAddonConfig};
-use crate::api::fleet_clustergroup::ClusterGroup;
+use crate::api::fleet_clustergroup::{ClusterGroup, GROUP_READY_CONDITION};
+use crate::controllers::controller::GetApi;
 
-use kube::api::PatchParams;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Condition;
+use kube::api::{Patch, PatchParams};
 
 use kube::runtime::controller::Action;
+use kube::runtime::events::{Event, EventType};
+use kube::{Resource as _, ResourceExt as _};
+use serde_json::json;
 
 use std::sync::Arc;
 
+use super::conditions;
 use super::controller::{
     Context, FleetBundle, FleetController, fetch_config, get_or_create, patch,
 };
-use super::{BundleResult, GroupSyncResult};
+use super::{BundleResult, GroupSyncError, GroupSyncResult};
 
 pub struct FleetClusterClassBundle {
+    cluster_class: ClusterClass,
     fleet_group: ClusterGroup,
     config: FleetAddonConfig,
 }
@@ -24,7 +31,7 @@ impl FleetBundle for FleetClusterClassBundle {
     async fn sync(&mut self, ctx: Arc<Context>) -> GroupSyncResult<Action> {
         if self.config.cluster_class_patch_enabled() {
             patch(
-                ctx,
+                ctx.clone(),
                 &mut self.fleet_group,
                 &PatchParams::apply("addon-provider-fleet"),
             )
@@ -33,10 +40,109 @@ impl FleetBundle for FleetClusterClassBundle {
             get_or_create(ctx.clone(), &self.fleet_group).await?
         };
 
+        self.sync_group_status(ctx).await?;
+
         Ok(Action::await_change())
     }
 }
 
+impl FleetClusterClassBundle {
+    /// Summarizes the status of all `ClusterGroups` generated for the class on the
+    /// `ClusterClass` conditions, publishing a Warning event when the readiness drops.
+    async fn sync_group_status(&self, ctx: Arc<Context>) -> GroupSyncResult<()> {
+        let groups = ctx
+            .class_groups(&self.cluster_class)
+            .await
+            .map_err(GroupSyncError::StatusLookup)?;
+        if groups.is_empty() {
+            return Ok(());
+        }
+
+        let condition = ClusterGroup::summarize_ready(groups.iter().map(AsRef::as_ref));
+        let was_ready = self
+            .cluster_class
+            .status
+            .as_ref()
+            .and_then(|status| status.conditions.as_ref())
+            .and_then(|conditions| conditions.iter().find(|c| c.type_ == GROUP_READY_CONDITION))
+            .is_none_or(|c| c.status != "False");
+
+        if was_ready && condition.status == "False" && !ctx.flags.dry_run {
+            match ctx
+                .diagnostics
+                .read()
+                .await
+                .recorder(ctx.client.clone())
+                .publish(
+                    &Event {
+                        type_: EventType::Warning,
+                        reason: condition.reason.clone(),
+                        note: Some(format!(
+                            "ClusterGroups of the ClusterClass are not ready: {}",
+                            condition.message
+                        )),
+                        action: "Summarizing".into(),
+                        secondary: None,
+                    },
+                    &self.cluster_class.object_ref(&()),
+                )
+                .await
+            {
+                // Events can't be created while the ClusterClass namespace is terminating
+                Err(kube::Error::Api(e)) if e.is_forbidden() => (),
+                e => e.map_err(GroupSyncError::Event)?,
+            }
+        }
+
+        self.cluster_class
+            .set_condition(ctx, condition)
+            .await
+            .map_err(GroupSyncError::ConditionError)
+    }
+}
+
+impl ClusterClass {
+    /// Sets a condition in the `ClusterClass` `status.conditions`, owned by the addon provider.
+    ///
+    /// The status is only patched when the condition differs from the observed one.
+    pub(crate) async fn set_condition(
+        &self,
+        ctx: Arc<Context>,
+        mut condition: Condition,
+    ) -> kube::Result<()> {
+        let existing = self
+            .status
+            .as_ref()
+            .and_then(|status| status.conditions.as_ref())
+            .and_then(|conditions| conditions.iter().find(|c| c.type_ == condition.type_));
+
+        condition.observed_generation = self.metadata.generation;
+        let Some(condition) = conditions::merge(existing, condition) else {
+            return Ok(());
+        };
+        if ctx.flags.dry_run {
+            return Ok(());
+        }
+
+        let patch = json!({
+            "apiVersion": ClusterClass::api_version(&()),
+            "kind": ClusterClass::kind(&()),
+            "status": {
+                "conditions": [condition],
+            },
+        });
+        ClusterClass::get_api(ctx.client.clone(), self.get_namespace())
+            .patch_status(
+                &self.name_any(),
+                &PatchParams::apply(&conditions::field_manager(&condition.type_)).force(),
+                &Patch::Apply(&patch),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
 impl FleetController for ClusterClass {
     type Bundle = FleetClusterClassBundle;
 
@@ -57,6 +163,7 @@ impl FleetController for ClusterClass {
         }
 
         Ok(Some(FleetClusterClassBundle {
+            cluster_class: self.clone(),
             fleet_group,
             config,
         }))
@@ -1,4 +1,3 @@
-use crate::api::capi_clusterclass::ClusterClass;
 use crate::api::fleet_clustergroup::ClusterGroup;
 use crate::controllers::controller::GetApi;
 
@@ -11,6 +10,7 @@ use std::ops::Deref;
 use std::sync::Arc;
 
 use super::controller::{Context, FLEET_FINALIZER, patch};
+use super::dry_run::DryRunOperation;
 use super::{GroupSyncResult, SyncError};
 
 impl ClusterGroup {
@@ -26,7 +26,7 @@ impl ClusterGroup {
 
     async fn sync(&mut self, ctx: Arc<Context>) -> GroupSyncResult<Action> {
         if let Some(cc_ref) = self.cluster_class_ref() {
-            let class = ctx.client.fetch::<ClusterClass>(&cc_ref).await?;
+            let class = ctx.cluster_class(&cc_ref).await?;
             self.labels_mut().extend(
                 class
                     .labels()
@@ -42,12 +42,24 @@ impl ClusterGroup {
         }
 
         if self.finalizers().iter().any(|f| f == FLEET_FINALIZER) {
-            self.finalizers_mut().retain(|f| f != FLEET_FINALIZER);
+            let finalizers: Vec<_> = self
+                .finalizers()
+                .iter()
+                .filter(|f| *f != FLEET_FINALIZER)
+                .collect();
+            let patch = json!({"metadata": {"finalizers": finalizers}});
+            if ctx.flags.dry_run {
+                ctx.dry_run
+                    .merge(self, DryRunOperation::Finalizers, &patch)
+                    .await;
+                return Ok(Action::await_change());
+            }
+
             let api = Self::get_api(ctx.client.clone(), self.get_namespace());
             api.patch(
                 &self.name_any(),
                 &PatchParams::default(),
-                &Patch::Merge(json!({"metadata": {"finalizers": self.finalizers()}})),
+                &Patch::Merge(&patch),
             )
             .await?;
         }
@@ -0,0 +1,251 @@
+use jiff::Timestamp;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
+
+/// Summary condition, `True` when the resource is reconciled without issues.
+pub const READY_CONDITION: &str = "Ready";
+/// kstatus condition, `True` when the controller can't make progress without intervention.
+pub const STALLED_CONDITION: &str = "Stalled";
+
+/// Reasons of the `False` conditions which only a configuration change can resolve.
+const TERMINAL_REASONS: &[&str] = &["Incompatible", "InvalidCertificate"];
+
+/// Field manager applying a single condition type to a map list of `status.conditions`.
+///
+/// An apply removes the list entries previously owned by the field manager and missing
+/// from the patch, so every condition type is owned by a dedicated manager.
+pub(crate) fn field_manager(type_: &str) -> String {
+    format!("addon-provider-fleet-{type_}")
+}
+
+/// Merges the condition into the previously observed condition of the same type,
+/// with the `meta.SetStatusCondition` semantics.
+///
+/// The `lastTransitionTime` is kept unless the status changes. Returns `None` if the
+/// observed condition is already up to date.
+pub(crate) fn merge(existing: Option<&Condition>, mut condition: Condition) -> Option<Condition> {
+    let Some(existing) = existing else {
+        return Some(condition);
+    };
+
+    if existing.status == condition.status {
+        if existing.reason == condition.reason
+            && existing.message == condition.message
+            && existing.observed_generation == condition.observed_generation
+        {
+            return None;
+        }
+        condition.last_transition_time = existing.last_transition_time.clone();
+    }
+
+    Some(condition)
+}
+
+/// Sets the condition in the list, replacing the condition of the same type.
+///
+/// Returns `true` if the list was changed.
+pub(crate) fn set_condition(conditions: &mut Vec<Condition>, condition: Condition) -> bool {
+    let index = conditions.iter().position(|c| c.type_ == condition.type_);
+    let Some(condition) = merge(index.map(|i| &conditions[i]), condition) else {
+        return false;
+    };
+
+    match index {
+        Some(i) => conditions[i] = condition,
+        None => conditions.push(condition),
+    }
+    true
+}
+
+/// Removes the condition of the given type. Returns `true` if the list was changed.
+pub(crate) fn remove_condition(conditions: &mut Vec<Condition>, type_: &str) -> bool {
+    let len = conditions.len();
+    conditions.retain(|c| c.type_ != type_);
+    conditions.len() != len
+}
+
+/// Sets the kstatus compatible `Ready` and `Stalled` summary conditions.
+///
+/// A reconcile error or any other condition with the `False` status marks the resource
+/// as not ready. It is stalled only if the error or the condition reason is terminal,
+/// retried errors keep `Stalled` at `False`. Otherwise the resource is ready, and `Stalled` is removed.
+pub(crate) fn summarize(
+    conditions: &mut Vec<Condition>,
+    generation: Option<i64>,
+    error: Option<&crate::Error>,
+) {
+    let blocker = match error {
+        Some(error) => Some((
+            "ReconcileError".to_string(),
+            format!("FleetAddonConfig reconcile error: {error}"),
+            error.is_terminal(),
+        )),
+        None => conditions
+            .iter()
+            .find(|c| {
+                c.status == "False" && c.type_ != READY_CONDITION && c.type_ != STALLED_CONDITION
+            })
+            .map(|c| {
+                (
+                    c.reason.clone(),
+                    format!("{}: {}", c.type_, c.message),
+                    TERMINAL_REASONS.contains(&c.reason.as_str()),
+                )
+            }),
+    };
+
+    let condition = |type_: &str, status: &str, reason: String, message: String| Condition {
+        last_transition_time: Time(Timestamp::now()),
+        message,
+        observed_generation: generation,
+        reason,
+        status: status.into(),
+        type_: type_.into(),
+    };
+
+    match blocker {
+        Some((reason, message, terminal)) => {
+            set_condition(
+                conditions,
+                condition(READY_CONDITION, "False", reason.clone(), message.clone()),
+            );
+            let stalled = if terminal { "True" } else { "False" };
+            set_condition(
+                conditions,
+                condition(STALLED_CONDITION, stalled, reason, message),
+            );
+        }
+        None => {
+            set_condition(
+                conditions,
+                condition(
+                    READY_CONDITION,
+                    "True",
+                    "Ready".into(),
+                    "Addon provider is ready".into(),
+                ),
+            );
+            remove_condition(conditions, STALLED_CONDITION);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::Timestamp;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
+
+    use std::io;
+
+    use crate::Error;
+    use crate::controllers::MaintenanceWindowError;
+    use crate::controllers::addon_config::AddonConfigSyncError;
+
+    use super::{READY_CONDITION, STALLED_CONDITION, set_condition, summarize};
+
+    fn condition(type_: &str, status: &str, message: &str, at: &str) -> Condition {
+        Condition {
+            last_transition_time: Time(at.parse::<Timestamp>().unwrap()),
+            message: message.into(),
+            observed_generation: Some(1),
+            reason: "Reason".into(),
+            status: status.into(),
+            type_: type_.into(),
+        }
+    }
+
+    #[test]
+    fn test_set_condition() {
+        let mut conditions = vec![];
+        let first = condition("Installed", "True", "installed", "2026-01-01T00:00:00Z");
+        assert!(set_condition(&mut conditions, first.clone()));
+        assert!(!set_condition(
+            &mut conditions,
+            condition("Installed", "True", "installed", "2026-01-02T00:00:00Z")
+        ));
+        assert_eq!(vec![first.clone()], conditions);
+
+        // Same status keeps the transition time
+        assert!(set_condition(
+            &mut conditions,
+            condition("Installed", "True", "upgraded", "2026-01-03T00:00:00Z")
+        ));
+        assert_eq!(
+            first.last_transition_time,
+            conditions[0].last_transition_time
+        );
+        assert_eq!("upgraded", conditions[0].message);
+
+        // Status change updates it
+        let failed = condition("Installed", "False", "failed", "2026-01-04T00:00:00Z");
+        assert!(set_condition(&mut conditions, failed.clone()));
+        assert_eq!(vec![failed], conditions);
+    }
+
+    #[test]
+    fn test_summarize() {
+        let mut conditions = vec![condition(
+            "FleetCompatible",
+            "False",
+            "unsupported",
+            "2026-01-01T00:00:00Z",
+        )];
+        summarize(&mut conditions, Some(1), None);
+        let types: Vec<_> = conditions.iter().map(|c| c.type_.as_str()).collect();
+        assert_eq!(
+            vec!["FleetCompatible", READY_CONDITION, STALLED_CONDITION],
+            types
+        );
+        assert_eq!("False", conditions[1].status);
+        assert_eq!("FleetCompatible: unsupported", conditions[1].message);
+
+        conditions.remove(0);
+        summarize(
+            &mut conditions,
+            Some(1),
+            Some(&Error::CommandError(io::Error::other("failed"))),
+        );
+        assert_eq!("ReconcileError", conditions[0].reason);
+
+        summarize(&mut conditions, Some(2), None);
+        assert_eq!(1, conditions.len());
+        assert_eq!("True", conditions[0].status);
+        assert_eq!(Some(2), conditions[0].observed_generation);
+    }
+
+    #[test]
+    fn test_summarize_stalled() {
+        let status = |conditions: &[_], type_| {
+            conditions
+                .iter()
+                .find(|c: &&Condition| c.type_ == type_)
+                .map(|c| c.status.clone())
+        };
+
+        // Transient errors are retried, the resource is not stalled
+        let mut conditions = vec![];
+        summarize(
+            &mut conditions,
+            Some(1),
+            Some(&Error::CommandError(io::Error::other("timeout"))),
+        );
+        assert_eq!(Some("False".into()), status(&conditions, READY_CONDITION));
+        assert_eq!(Some("False".into()), status(&conditions, STALLED_CONDITION));
+
+        let invalid = Error::FleetConfigError(AddonConfigSyncError::MaintenanceWindow(
+            MaintenanceWindowError::NoOccurrence("0 0 30 2 *".into()),
+        ));
+        summarize(&mut conditions, Some(1), Some(&invalid));
+        assert_eq!(Some("True".into()), status(&conditions, STALLED_CONDITION));
+
+        // Terminal condition reasons stall the resource
+        let mut conditions = vec![condition(
+            "FleetCompatible",
+            "False",
+            "unsupported",
+            "2026-01-01T00:00:00Z",
+        )];
+        conditions[0].reason = "Incompatible".into();
+        summarize(&mut conditions, Some(1), None);
+        assert_eq!(Some("True".into()), status(&conditions, STALLED_CONDITION));
+    }
+}
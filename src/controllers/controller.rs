@@ -1,5 +1,6 @@
 use crate::api::comparable::ResourceDiff;
 use crate::api::fleet_addon_config::FleetAddonConfig;
+use crate::controller::Flags;
 use crate::controllers::PatchError;
 use crate::metrics::Diagnostics;
 use crate::multi_dispatcher::{BroadcastStream, MultiDispatcher, typed_gvk};
@@ -10,15 +11,17 @@ use futures::stream::SelectAll;
 use jiff::Timestamp;
 use k8s_openapi::{ClusterResourceScope, NamespaceResourceScope};
 
-use kube::api::{DynamicObject, Patch, PatchParams, PostParams};
+use kube::api::{DeleteParams, DynamicObject, Patch, PatchParams, PostParams};
 
 use kube::runtime::events::{Event, EventType};
 use kube::runtime::{finalizer, watcher};
 
 use kube::{api::Api, client::Client, runtime::controller::Action};
 
+use semver::Version;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
+use serde_json::json;
 use tracing::field::display;
 
 use std::fmt::Debug;
@@ -27,6 +30,9 @@ use std::sync::Arc;
 use tokio::sync::{Barrier, RwLock};
 use tracing::{self, Span, debug, info, instrument};
 
+use super::dry_run::{DryRun, DryRunOperation};
+use super::import_progress::ImportProgress;
+use super::stores::Stores;
 use super::{
     BundleResult, ConfigFetchResult, GetOrCreateError, GetOrCreateResult, PatchResult, SyncError,
 };
@@ -52,8 +58,18 @@ pub struct Context {
     pub stream: BroadcastStream<DynamicStream>,
     // k8s minor version
     pub version: u32,
+    // k8s api server version
+    pub kube_version: Version,
     // Controller readiness barrier
     pub barrier: Arc<Barrier>,
+    // Reflector stores shared with the reconcilers
+    pub stores: Stores,
+    // Controller command line flags
+    pub flags: Flags,
+    // Import progress of the reconciled clusters
+    pub imports: ImportProgress,
+    // Changes computed instead of the writes in the dry-run mode
+    pub dry_run: DryRun,
 }
 
 #[instrument(skip_all, fields(name = res.name_any(), namespace = res.namespace(), api_version = typed_gvk::<R>(&()).api_version(), kind = R::kind(&()).to_string()), err)]
@@ -75,6 +91,11 @@ where
         return Ok(Action::await_change());
     }
 
+    if ctx.flags.dry_run {
+        ctx.dry_run.create(res).await;
+        return Ok(Action::await_change());
+    }
+
     api.create(&PostParams::default(), res)
         .await
         .map_err(GetOrCreateError::Create)?;
@@ -110,6 +131,26 @@ where
     Ok(Action::await_change())
 }
 
+/// Deletes the object, recording the deletion instead in the dry-run mode.
+///
+/// Objects already removed are ignored.
+pub(crate) async fn delete<K, R>(ctx: Arc<Context>, api: &Api<K>, res: &R) -> kube::Result<()>
+where
+    K: kube::Resource + Clone + DeserializeOwned + Debug,
+    R: kube::Resource<DynamicType = ()> + kube::ResourceExt + Serialize,
+{
+    if ctx.flags.dry_run {
+        ctx.dry_run.delete(res).await;
+        return Ok(());
+    }
+
+    match api.delete(&res.name_any(), &DeleteParams::default()).await {
+        // Object is already removed
+        Err(kube::Error::Api(e)) if e.code == 404 => Ok(()),
+        e => e.map(|_| ()),
+    }
+}
+
 #[instrument(skip_all, fields(name = res.name_any(), namespace = res.namespace(), api_version = typed_gvk::<R>(&()).api_version(), kind = R::kind(&()).to_string()), err)]
 pub(crate) async fn patch<R>(
     ctx: Arc<Context>,
@@ -125,14 +166,22 @@ where
     res.meta_mut().managed_fields = None;
 
     // Perform patch after comparison
-    if let Some(existing) = api
+    let existing = api
         .get_opt(&res.name_any())
         .await
-        .map_err(PatchError::Get)?
+        .map_err(PatchError::Get)?;
+    if let Some(existing) = &existing
+        && !res.diff(existing)
     {
-        if !res.diff(&existing) {
-            return Ok(Action::await_change());
+        return Ok(Action::await_change());
+    }
+
+    if ctx.flags.dry_run {
+        match &existing {
+            Some(existing) => ctx.dry_run.apply(existing, res).await,
+            None => ctx.dry_run.create(res).await,
         }
+        return Ok(Action::await_change());
     }
 
     api.patch(&res.name_any(), pp, &Patch::Apply(&res))
@@ -293,6 +342,10 @@ where
         let api = Self::get_api(ctx.client.clone(), self.get_namespace());
         debug!("Reconciling");
 
+        if ctx.flags.dry_run {
+            return self.reconcile_dry_run(ctx).await;
+        }
+
         finalizer(&api, FLEET_FINALIZER, self, |event| async {
             match event {
                 finalizer::Event::Apply(c) => match c.to_bundle(ctx.clone()).await? {
@@ -310,6 +363,33 @@ where
         .map_err(|e| Error::FinalizerError(Box::new(e)))
     }
 
+    /// Reconciles without the finalizer handling, recording the finalizer changes instead.
+    ///
+    /// Cleanup is skipped for the deleted objects.
+    async fn reconcile_dry_run(self: Arc<Self>, ctx: Arc<Context>) -> crate::Result<Action> {
+        let mut finalizers = self.finalizers().to_vec();
+        let deleting = self.meta().deletion_timestamp.is_some();
+        if deleting {
+            finalizers.retain(|f| f != FLEET_FINALIZER);
+        } else if !finalizers.iter().any(|f| f == FLEET_FINALIZER) {
+            finalizers.push(FLEET_FINALIZER.to_string());
+        }
+
+        let patch = json!({"metadata": {"finalizers": finalizers}});
+        ctx.dry_run
+            .merge(self.as_ref(), DryRunOperation::Finalizers, &patch)
+            .await;
+        if deleting {
+            info!("Skipping cleanup in the dry-run mode");
+            return Ok(Action::await_change());
+        }
+
+        match self.to_bundle(ctx.clone()).await? {
+            Some(mut bundle) => Ok(bundle.sync(ctx).await.map_err(Into::<SyncError>::into)?),
+            _ => Ok(Action::await_change()),
+        }
+    }
+
     async fn cleanup(&self, ctx: Arc<Context>) -> crate::Result<Action> {
         if let Some(mut bundle) = self.to_bundle(ctx.clone()).await? {
             return Ok(bundle.cleanup(ctx).await?);
@@ -0,0 +1,336 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use jiff::Timestamp;
+use json_patch::Patch;
+use kube::Resource;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+/// Write skipped in the dry-run mode.
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DryRunOperation {
+    /// Object creation
+    Create,
+    /// Server-side apply of the desired object
+    Apply,
+    /// Finalizers update
+    Finalizers,
+    /// Annotations update
+    Annotations,
+    /// Object deletion
+    Delete,
+    /// Status update
+    Status,
+}
+
+/// Difference between the live and the desired object, computed instead of a write.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunDiff {
+    pub api_version: String,
+    pub kind: String,
+    pub namespace: Option<String>,
+    pub name: String,
+    pub operation: DryRunOperation,
+    /// JSON patch from the live object to the desired object
+    pub patch: Patch,
+    pub time: Timestamp,
+}
+
+/// Helm operation planned instead of running it in the dry-run mode.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunHelmOperation {
+    pub release: String,
+    pub operation: String,
+    /// Chart version passed to helm, the latest chart version is used when unset
+    pub version: Option<String>,
+    /// Helm command arguments
+    pub args: Vec<String>,
+    pub time: Timestamp,
+}
+
+/// Changes recorded in the dry-run mode, served on the `/dryrun` endpoint.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunReport {
+    pub diffs: Vec<DryRunDiff>,
+    pub helm_operations: Vec<DryRunHelmOperation>,
+}
+
+type DiffKey = (String, Option<String>, String, DryRunOperation);
+
+/// Latest dry-run diff of every object, exposed by the web server.
+#[derive(Clone, Default)]
+pub struct DryRun {
+    diffs: Arc<RwLock<BTreeMap<DiffKey, DryRunDiff>>>,
+    helm_operations: Arc<RwLock<BTreeMap<String, DryRunHelmOperation>>>,
+}
+
+impl DryRun {
+    /// Returns the recorded diffs.
+    pub async fn diffs(&self) -> Vec<DryRunDiff> {
+        self.diffs.read().await.values().cloned().collect()
+    }
+
+    /// Returns the latest planned helm operation of every release.
+    pub async fn helm_operations(&self) -> Vec<DryRunHelmOperation> {
+        self.helm_operations
+            .read()
+            .await
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the recorded diffs and helm operations.
+    pub async fn report(&self) -> DryRunReport {
+        DryRunReport {
+            diffs: self.diffs().await,
+            helm_operations: self.helm_operations().await,
+        }
+    }
+
+    /// Records the helm command, as `<operation> <release> [args]`, instead of running it.
+    pub(crate) async fn helm(&self, args: &[String]) {
+        let operation = args.first().cloned().unwrap_or_default();
+        let release = args.get(1).cloned().unwrap_or_default();
+        let version = args
+            .iter()
+            .skip_while(|arg| *arg != "--version")
+            .nth(1)
+            .cloned();
+
+        info!(release, "Dry-run helm {operation}: {}", args.join(" "));
+        let planned = DryRunHelmOperation {
+            release: release.clone(),
+            operation,
+            version,
+            args: args.to_vec(),
+            time: Timestamp::now(),
+        };
+        self.helm_operations.write().await.insert(release, planned);
+    }
+
+    /// Records the creation of the desired object.
+    pub(crate) async fn create<R>(&self, desired: &R)
+    where
+        R: Resource<DynamicType = ()> + Serialize,
+    {
+        self.record(desired, DryRunOperation::Create, Value::Null, desired)
+            .await;
+    }
+
+    /// Records the deletion of the live object.
+    pub(crate) async fn delete<R>(&self, live: &R)
+    where
+        R: Resource<DynamicType = ()> + Serialize,
+    {
+        let live_value = serde_json::to_value(live).unwrap_or_default();
+        self.record(live, DryRunOperation::Delete, live_value, Value::Null)
+            .await;
+    }
+
+    /// Records the changes of the desired object, merged into the live object.
+    ///
+    /// Fields not set in the desired object are kept.
+    pub(crate) async fn apply<R>(&self, live: &R, desired: &R)
+    where
+        R: Resource<DynamicType = ()> + Serialize,
+    {
+        let mut patch = serde_json::to_value(desired).unwrap_or_default();
+        strip_nulls(&mut patch);
+        self.merge(live, DryRunOperation::Apply, &patch).await;
+    }
+
+    /// Records the changes of the JSON merge patch applied to the live object.
+    pub(crate) async fn merge<R>(&self, live: &R, operation: DryRunOperation, patch: &Value)
+    where
+        R: Resource<DynamicType = ()> + Serialize,
+    {
+        let mut live_value = serde_json::to_value(live).unwrap_or_default();
+        if let Some(metadata) = live_value
+            .get_mut("metadata")
+            .and_then(Value::as_object_mut)
+        {
+            metadata.remove("managedFields");
+        }
+
+        let mut desired = live_value.clone();
+        json_patch::merge(&mut desired, patch);
+        self.record(live, operation, live_value, desired).await;
+    }
+
+    async fn record<R>(
+        &self,
+        obj: &R,
+        operation: DryRunOperation,
+        live: Value,
+        desired: impl Serialize,
+    ) where
+        R: Resource<DynamicType = ()>,
+    {
+        let desired = match serde_json::to_value(desired) {
+            Ok(desired) => desired,
+            Err(e) => {
+                warn!("Failed to encode the dry-run object: {e}");
+                return;
+            }
+        };
+
+        let meta = obj.meta();
+        let name = meta.name.clone().unwrap_or_default();
+        let key = (
+            R::kind(&()).to_string(),
+            meta.namespace.clone(),
+            name,
+            operation,
+        );
+        let patch = json_patch::diff(&live, &desired);
+        if patch.0.is_empty() {
+            self.diffs.write().await.remove(&key);
+            return;
+        }
+
+        info!(
+            kind = key.0,
+            namespace = key.1,
+            name = key.2,
+            "Dry-run {operation:?}: {}",
+            serde_json::to_string(&patch).unwrap_or_default()
+        );
+        let diff = DryRunDiff {
+            api_version: R::api_version(&()).to_string(),
+            kind: key.0.clone(),
+            namespace: key.1.clone(),
+            name: key.2.clone(),
+            operation,
+            patch,
+            time: Timestamp::now(),
+        };
+        self.diffs.write().await.insert(key, diff);
+    }
+}
+
+/// Removes the unset fields, which would otherwise delete the live fields in a merge patch.
+fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use k8s_openapi::api::core::v1::Namespace;
+    use kube::api::ObjectMeta;
+    use serde_json::json;
+
+    use super::{DryRun, DryRunOperation};
+
+    fn namespace(annotations: &[(&str, &str)]) -> Namespace {
+        Namespace {
+            metadata: ObjectMeta {
+                name: Some("default".into()),
+                annotations: Some(
+                    annotations
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect::<BTreeMap<_, _>>(),
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_diffs() {
+        let dry_run = DryRun::default();
+        let live = namespace(&[("other", "value")]);
+
+        dry_run.apply(&live, &namespace(&[("fleet", "true")])).await;
+        let diffs = dry_run.diffs().await;
+        assert_eq!(1, diffs.len());
+        assert_eq!(
+            json!([{
+                "op": "add",
+                "path": "/metadata/annotations/fleet",
+                "value": "true",
+            }]),
+            serde_json::to_value(&diffs[0].patch).unwrap()
+        );
+
+        // Unchanged objects drop the previous diff
+        dry_run.apply(&live, &live).await;
+        dry_run
+            .merge(
+                &live,
+                DryRunOperation::Annotations,
+                &json!({"metadata": {"annotations": {"other": null}}}),
+            )
+            .await;
+        let diffs = dry_run.diffs().await;
+        assert_eq!(1, diffs.len());
+        assert_eq!(DryRunOperation::Annotations, diffs[0].operation);
+
+        dry_run.delete(&live).await;
+        let diffs = dry_run.diffs().await;
+        assert_eq!(2, diffs.len());
+        assert_eq!(
+            json!([{"op": "replace", "path": "", "value": null}]),
+            serde_json::to_value(&diffs[1].patch).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_helm_operations() {
+        let dry_run = DryRun::default();
+        let args = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        dry_run
+            .helm(&args(&[
+                "install",
+                "fleet-crd",
+                "fleet/fleet-crd",
+                "--wait",
+            ]))
+            .await;
+        dry_run
+            .helm(&args(&[
+                "install",
+                "fleet",
+                "fleet/fleet",
+                "--version",
+                "0.13.1",
+            ]))
+            .await;
+        dry_run
+            .helm(&args(&[
+                "upgrade",
+                "fleet",
+                "fleet/fleet",
+                "--version",
+                "0.14.0",
+            ]))
+            .await;
+
+        let report = dry_run.report().await;
+        assert!(report.diffs.is_empty());
+        assert_eq!(2, report.helm_operations.len());
+        assert_eq!("fleet", report.helm_operations[0].release);
+        assert_eq!("upgrade", report.helm_operations[0].operation);
+        assert_eq!(Some("0.14.0"), report.helm_operations[0].version.as_deref());
+        assert_eq!("fleet-crd", report.helm_operations[1].release);
+        assert_eq!(None, report.helm_operations[1].version);
+    }
+}
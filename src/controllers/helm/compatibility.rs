@@ -0,0 +1,112 @@
+use semver::Version;
+
+use super::{IncompatibleVersion, install::ChartMetadata, version::satisfies};
+
+/// Fleet minor versions supported by the `fleet-api-rs` types the provider is built with.
+pub(crate) const SUPPORTED_FLEET_VERSIONS: &[&str] = &["0.12", "0.13", "0.14"];
+
+/// Checks that the Fleet chart can be installed on the management cluster.
+///
+/// The Fleet version must be supported by the provider, and the Kubernetes version must satisfy
+/// the chart `kubeVersion` constraint, if the chart declares one. Pre-release and build suffixes
+/// of the server version, like `-rc.1` or `-eks-a737599`, are ignored for the constraint.
+pub(crate) fn check(
+    metadata: &ChartMetadata,
+    kubernetes: &Version,
+) -> Result<(), IncompatibleVersion> {
+    let fleet = metadata.app_version.as_deref().unwrap_or(&metadata.version);
+    let fleet_version = Version::parse(fleet.strip_prefix('v').unwrap_or(fleet))
+        .map_err(|_| IncompatibleVersion::InvalidVersion(fleet.to_string()))?;
+
+    let minor = format!("{}.{}", fleet_version.major, fleet_version.minor);
+    if !SUPPORTED_FLEET_VERSIONS.contains(&minor.as_str()) {
+        return Err(IncompatibleVersion::UnsupportedFleet {
+            version: fleet.to_string(),
+            supported: SUPPORTED_FLEET_VERSIONS.join(", "),
+        });
+    }
+
+    if let Some(constraint) = metadata.kube_version.as_deref() {
+        let release = Version::new(kubernetes.major, kubernetes.minor, kubernetes.patch);
+        let compatible = satisfies(constraint, &release).map_err(|_| {
+            IncompatibleVersion::InvalidKubeVersion {
+                version: fleet.to_string(),
+                constraint: constraint.to_string(),
+            }
+        })?;
+        if !compatible {
+            return Err(IncompatibleVersion::UnsupportedKubernetes {
+                version: fleet.to_string(),
+                kubernetes: kubernetes.to_string(),
+                constraint: constraint.to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::Version;
+
+    use crate::controllers::helm::{IncompatibleVersion, install::ChartMetadata};
+
+    use super::check;
+
+    fn chart(app_version: &str, kube_version: Option<&str>) -> ChartMetadata {
+        ChartMetadata {
+            version: app_version.into(),
+            app_version: Some(app_version.into()),
+            kube_version: kube_version.map(Into::into),
+        }
+    }
+
+    fn kube(version: &str) -> Version {
+        Version::parse(version).unwrap()
+    }
+
+    #[test]
+    fn test_check() {
+        assert!(check(&chart("0.12.5", None), &kube("1.30.0")).is_ok());
+        assert!(check(&chart("v0.13.1", Some(">= 1.28.0-0")), &kube("1.33.0")).is_ok());
+        assert!(check(&chart("0.14.0", Some(">=1.30 <1.35")), &kube("1.34.0")).is_ok());
+
+        assert!(matches!(
+            check(&chart("0.11.9", None), &kube("1.30.0")),
+            Err(IncompatibleVersion::UnsupportedFleet { .. })
+        ));
+        assert!(matches!(
+            check(&chart("0.15.0", None), &kube("1.30.0")),
+            Err(IncompatibleVersion::UnsupportedFleet { .. })
+        ));
+        assert!(matches!(
+            check(&chart("0.14.0", Some(">=1.30 <1.35")), &kube("1.29.0")),
+            Err(IncompatibleVersion::UnsupportedKubernetes { .. })
+        ));
+        assert!(matches!(
+            check(&chart("0.14.0", Some("newer than 1.30")), &kube("1.30.0")),
+            Err(IncompatibleVersion::InvalidKubeVersion { .. })
+        ));
+        assert!(matches!(
+            check(&chart("dev", None), &kube("1.30.0")),
+            Err(IncompatibleVersion::InvalidVersion(_))
+        ));
+    }
+
+    #[test]
+    fn test_check_server_version() {
+        let chart = chart("0.14.0", Some(">=1.30.2 <1.35"));
+        assert!(check(&chart, &kube("1.30.2")).is_ok());
+        assert!(check(&chart, &kube("1.30.4+k3s1")).is_ok());
+        assert!(check(&chart, &kube("1.31.0-rc.1")).is_ok());
+        assert!(check(&chart, &kube("1.32.1-eks-a737599")).is_ok());
+
+        match check(&chart, &kube("1.30.1-gke.1200")) {
+            Err(IncompatibleVersion::UnsupportedKubernetes { kubernetes, .. }) => {
+                assert_eq!(kubernetes, "1.30.1-gke.1200");
+            }
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+}
@@ -3,11 +3,12 @@ use std::{fmt::Display, process::Stdio};
 use serde::Deserialize;
 use tokio::process::{Child, Command};
 
-use crate::api::fleet_addon_config::{FeatureGates, Install};
+use crate::api::fleet_addon_config::{FeatureFlagResult, FeatureGates, FleetSettingsSpec, Install};
 
 use super::{
-    FleetCRDInstallResult, FleetInstallResult, MetadataGetResult, RepoAddResult, RepoSearchResult,
-    RepoUpdateResult,
+    ChartShowResult, FleetCRDInstallResult, FleetInstallResult, FleetUninstallResult,
+    MetadataGetResult, RepoAddResult, RepoSearchResult, RepoUpdateResult,
+    runner::{HelmOutput, HelmRunner},
 };
 
 #[allow(clippy::struct_excessive_bools)]
@@ -24,12 +25,16 @@ pub struct FleetChart {
     pub bootstrap_local_cluster: bool,
 
     pub feature_gates: FeatureGates,
+
+    /// Runs mutating helm operations. Read-only operations always run in the controller container.
+    pub runner: HelmRunner,
 }
 
 #[derive(PartialEq)]
 pub enum HelmOperation {
     Install,
     Upgrade,
+    Uninstall,
 }
 
 impl Display for HelmOperation {
@@ -37,6 +42,7 @@ impl Display for HelmOperation {
         match self {
             HelmOperation::Install => f.write_str("install"),
             HelmOperation::Upgrade => f.write_str("upgrade"),
+            HelmOperation::Uninstall => f.write_str("uninstall"),
         }
     }
 }
@@ -49,9 +55,19 @@ pub struct ChartInfo {
     pub status: String,
 }
 
+/// Chart metadata from `helm show chart`.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartMetadata {
+    pub version: String,
+    pub app_version: Option<String>,
+    pub kube_version: Option<String>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct ChartSearch {
     pub name: String,
+    pub version: String,
     pub app_version: String,
 }
 
@@ -80,6 +96,9 @@ impl FleetChart {
 
     /// Searches the fleet helm repository for charts.
     ///
+    /// Runs in the controller container with any runner, reading the repository index cached
+    /// by `add_repo` and `update_repo`. It needs no cluster access, and Jobs start without the cache.
+    ///
     /// # Errors
     ///
     /// This function will return an error if the helm command fails to spawn or the output cannot be parsed.
@@ -95,8 +114,51 @@ impl FleetChart {
         Ok(serde_json::from_str(output)?)
     }
 
+    /// Searches the fleet helm repository for all available versions of a chart.
+    ///
+    /// Runs in the controller container, like `search_repo`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the helm command fails to spawn or the output cannot be parsed.
+    pub async fn search_versions(&self, chart: &str) -> RepoSearchResult<Vec<ChartSearch>> {
+        let result = Command::new("helm")
+            .stdout(Stdio::piped())
+            .args(["search", "repo", chart, "--versions", "-o", "json"])
+            .spawn()?
+            .wait_with_output()
+            .await?;
+
+        let output = &String::from_utf8(result.stdout)?;
+        let versions: Vec<ChartSearch> = serde_json::from_str(output)?;
+        Ok(versions.into_iter().filter(|r| r.name == chart).collect())
+    }
+
+    /// Shows the `Chart.yaml` metadata of a fleet repository chart version.
+    ///
+    /// Runs in the controller container, like `search_repo`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the helm command fails to spawn or the output cannot be parsed.
+    pub async fn show_chart(&self, chart: &str, version: &str) -> ChartShowResult<ChartMetadata> {
+        let result = Command::new("helm")
+            .stdout(Stdio::piped())
+            .args(["show", "chart", chart, "--version", version])
+            .spawn()?
+            .wait_with_output()
+            .await?;
+
+        let output = &String::from_utf8(result.stdout)?;
+        Ok(serde_yaml::from_str(output)?)
+    }
+
     /// Gets metadata for a specific chart.
     ///
+    /// Runs in the controller container with any runner. Listing releases only reads the
+    /// release secrets, which the controller is allowed to, while a Job per lookup would
+    /// delay every reconcile by a Pod startup.
+    ///
     /// # Errors
     ///
     /// This function will return an error if the helm command fails to spawn or the output cannot be parsed.
@@ -125,22 +187,12 @@ impl FleetChart {
     /// # Errors
     ///
     /// This function will return an error if the helm command fails to spawn.
-    pub fn fleet(&self, operation: &HelmOperation) -> FleetInstallResult<Child> {
+    pub async fn fleet(&self, operation: &HelmOperation) -> FleetInstallResult<HelmOutput> {
         let mut install = Command::new("helm");
 
-        install.args([&operation.to_string(), "fleet", "fleet/fleet"]);
-        let oci = self.feature_gates.experimental_oci_storage;
-        let helm_ops = self.feature_gates.experimental_oci_storage;
-        install.args([
-            "--set-string",
-            "extraEnv[0].name=EXPERIMENTAL_OCI_STORAGE",
-            "--set-string",
-            &format!("extraEnv[0].value={oci}",),
-            "--set-string",
-            "extraEnv[1].name=EXPERIMENTAL_HELM_OPS",
-            "--set-string",
-            &format!("extraEnv[1].value={helm_ops}",),
-        ]);
+        install.args([&operation.to_string(), "fleet"]);
+        install.args(self.runner.chart_ref(&self.repo, "fleet"));
+        install.args(self.feature_flag_args()?);
 
         if operation == &HelmOperation::Upgrade {
             install.arg("--reuse-values");
@@ -156,7 +208,7 @@ impl FleetChart {
 
         match self.version.clone().unwrap_or_default() {
             Install::FollowLatest(_) => {}
-            Install::Version(version) => {
+            Install::Version(version) | Install::Constraint(version) => {
                 install.args(["--version", &version]);
             }
         }
@@ -170,7 +222,51 @@ impl FleetChart {
             &format!("bootstrap.enabled={}", self.bootstrap_local_cluster),
         ]);
 
-        Ok(install.spawn()?)
+        Ok(self.runner.run(install).await?)
+    }
+
+    /// Builds `--set-string` arguments for the fleet chart `extraEnv` from the feature gates.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the feature flags are not valid.
+    pub fn feature_flag_args(&self) -> FeatureFlagResult<Vec<String>> {
+        let mut values = FleetSettingsSpec::default();
+        self.feature_gates.merge_features(&mut values)?;
+
+        Ok(values
+            .fleet
+            .extra_env
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .flat_map(|(i, env)| {
+                [
+                    "--set-string".to_string(),
+                    format!("extraEnv[{i}].name={}", env.name),
+                    "--set-string".to_string(),
+                    format!("extraEnv[{i}].value={}", env.value),
+                ]
+            })
+            .collect())
+    }
+
+    /// Uninstalls a release installed from the fleet repository.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the helm command fails to spawn.
+    pub async fn uninstall(&self, release: &ChartInfo) -> FleetUninstallResult<HelmOutput> {
+        let mut uninstall = Command::new("helm");
+        uninstall.args([
+            &HelmOperation::Uninstall.to_string(),
+            &release.name,
+            "--namespace",
+            &release.namespace,
+            "--wait",
+        ]);
+
+        Ok(self.runner.run(uninstall).await?)
     }
 
     /// Installs or upgrades the fleet-crd chart.
@@ -178,10 +274,11 @@ impl FleetChart {
     /// # Errors
     ///
     /// This function will return an error if the helm command fails to spawn.
-    pub fn fleet_crds(&self, operation: &HelmOperation) -> FleetCRDInstallResult<Child> {
+    pub async fn fleet_crds(&self, operation: &HelmOperation) -> FleetCRDInstallResult<HelmOutput> {
         let mut install = Command::new("helm");
 
-        install.args([&operation.to_string(), "fleet-crd", "fleet/fleet-crd"]);
+        install.args([&operation.to_string(), "fleet-crd"]);
+        install.args(self.runner.chart_ref(&self.repo, "fleet-crd"));
 
         if operation == &HelmOperation::Upgrade {
             install.arg("--reuse-values");
@@ -197,7 +294,7 @@ impl FleetChart {
 
         match self.version.clone().unwrap_or_default() {
             Install::FollowLatest(_) => {}
-            Install::Version(version) => {
+            Install::Version(version) | Install::Constraint(version) => {
                 install.args(["--version", &version]);
             }
         }
@@ -206,6 +303,62 @@ impl FleetChart {
             install.arg("--wait");
         }
 
-        Ok(install.spawn()?)
+        Ok(self.runner.run(install).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::fleet_addon_config::{FeatureGates, FleetSettingsSpec};
+
+    use super::FleetChart;
+
+    #[test]
+    fn test_feature_flag_args_match_config_map() {
+        let feature_gates = FeatureGates {
+            experimental_oci_storage: false,
+            flags: Some([("EXPERIMENTAL_COPY_RESOURCES_DOWNSTREAM".to_string(), true)].into()),
+            ..Default::default()
+        };
+        let chart = FleetChart {
+            feature_gates: feature_gates.clone(),
+            ..Default::default()
+        };
+
+        let mut settings = FleetSettingsSpec::default();
+        feature_gates.merge_features(&mut settings).unwrap();
+        let config_map: Vec<String> = settings
+            .fleet
+            .extra_env
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .flat_map(|(i, env)| {
+                [
+                    format!("extraEnv[{i}].name={}", env.name),
+                    format!("extraEnv[{i}].value={}", env.value),
+                ]
+            })
+            .collect();
+
+        let helm: Vec<String> = chart
+            .feature_flag_args()
+            .unwrap()
+            .into_iter()
+            .filter(|arg| arg != "--set-string")
+            .collect();
+
+        assert_eq!(config_map, helm);
+        assert_eq!(
+            helm,
+            [
+                "extraEnv[0].name=EXPERIMENTAL_HELM_OPS",
+                "extraEnv[0].value=true",
+                "extraEnv[1].name=EXPERIMENTAL_OCI_STORAGE",
+                "extraEnv[1].value=false",
+                "extraEnv[2].name=EXPERIMENTAL_COPY_RESOURCES_DOWNSTREAM",
+                "extraEnv[2].value=true",
+            ]
+        );
     }
 }
@@ -2,12 +2,20 @@ use std::io;
 
 use thiserror::Error;
 
+use crate::api::fleet_addon_config::FeatureFlagError;
+
 pub type FleetInstallResult<T> = std::result::Result<T, FleetInstallError>;
 
 #[derive(Error, Debug)]
 pub enum FleetInstallError {
     #[error("Fleet install error: {0}")]
     FleetInstall(#[from] io::Error),
+
+    #[error("Fleet feature flags error: {0}")]
+    FeatureFlags(#[from] FeatureFlagError),
+
+    #[error("Fleet install run error: {0}")]
+    Run(#[from] HelmRunError),
 }
 
 pub type FleetPatchResult<T> = std::result::Result<T, FleetPatchError>;
@@ -18,12 +26,49 @@ pub enum FleetPatchError {
     FleetPatch(#[from] io::Error),
 }
 
+pub type FleetUninstallResult<T> = std::result::Result<T, FleetUninstallError>;
+
+#[derive(Error, Debug)]
+pub enum FleetUninstallError {
+    #[error("Fleet uninstall error: {0}")]
+    FleetUninstall(#[from] io::Error),
+
+    #[error("Fleet uninstall run error: {0}")]
+    Run(#[from] HelmRunError),
+}
+
 pub type FleetCRDInstallResult<T> = std::result::Result<T, FleetCRDInstallError>;
 
 #[derive(Error, Debug)]
 pub enum FleetCRDInstallError {
     #[error("CRD install error: {0}")]
     CRDInstall(#[from] io::Error),
+
+    #[error("CRD install run error: {0}")]
+    Run(#[from] HelmRunError),
+}
+
+pub type HelmRunResult<T> = std::result::Result<T, HelmRunError>;
+
+#[derive(Error, Debug)]
+pub enum HelmRunError {
+    #[error("Helm process error: {0}")]
+    Process(#[from] io::Error),
+
+    #[error("Decode error: {0}")]
+    UTF8Error(#[from] std::string::FromUtf8Error),
+
+    #[error("Helm job error: {0}")]
+    Job(#[source] kube::Error),
+
+    #[error("Helm job logs error: {0}")]
+    Logs(#[source] kube::Error),
+
+    #[error("Helm job wait error: {0}")]
+    Wait(#[from] kube::runtime::wait::Error),
+
+    #[error("Helm job {0} did not finish in time")]
+    Timeout(String),
 }
 
 pub type RepoAddResult<T> = std::result::Result<T, RepoAddError>;
@@ -70,4 +115,56 @@ pub enum MetadataGetError {
     DeserializeInfoError(#[from] serde_json::Error),
 }
 
+pub type VersionResolveResult<T> = std::result::Result<T, VersionResolveError>;
+
+#[derive(Error, Debug)]
+pub enum VersionResolveError {
+    #[error("Invalid version constraint `{0}`: {1}")]
+    Parse(String, #[source] semver::Error),
+
+    #[error("No fleet chart version satisfies the constraint `{0}`")]
+    NoMatch(String),
+}
+
+pub type ChartShowResult<T> = std::result::Result<T, ChartShowError>;
+
+#[derive(Error, Debug)]
+pub enum ChartShowError {
+    #[error("Chart show error: {0}")]
+    ChartShow(#[from] io::Error),
+
+    #[error("Decode error: {0}")]
+    UTF8Error(#[from] std::string::FromUtf8Error),
+
+    #[error("Deserialize chart error: {0}")]
+    DeserializeChartError(#[from] serde_yaml::Error),
+}
+
+/// Reason a Fleet version can't be installed on the management cluster.
+#[derive(Error, Debug)]
+pub enum IncompatibleVersion {
+    #[error("Fleet version `{0}` is not a valid semver version")]
+    InvalidVersion(String),
+
+    #[error(
+        "Fleet version `{version}` is not supported by the addon provider, supported versions: {supported}"
+    )]
+    UnsupportedFleet { version: String, supported: String },
+
+    #[error("Fleet version `{version}` declares an invalid kubeVersion `{constraint}`")]
+    InvalidKubeVersion { version: String, constraint: String },
+
+    #[error(
+        "Fleet version `{version}` requires Kubernetes `{constraint}`, management cluster runs {kubernetes}"
+    )]
+    UnsupportedKubernetes {
+        version: String,
+        kubernetes: String,
+        constraint: String,
+    },
+}
+
+pub mod compatibility;
 pub mod install;
+pub mod runner;
+pub mod version;
@@ -0,0 +1,277 @@
+use std::{process::Stdio, time::Duration};
+
+use k8s_openapi::{
+    api::{
+        batch::v1::{Job, JobSpec},
+        core::v1::{Container, Pod, PodSpec, PodTemplateSpec},
+    },
+    apimachinery::pkg::apis::meta::v1::OwnerReference,
+};
+use kube::{
+    Api, Client,
+    api::{DeleteParams, ListParams, LogParams, ObjectMeta, PostParams},
+    runtime::wait::await_condition,
+};
+use tokio::process::Command;
+use tracing::{debug, info};
+
+use crate::api::fleet_addon_config::short_hash;
+use crate::controllers::dry_run::DryRun;
+
+use super::{HelmRunError, HelmRunResult};
+
+/// Maximum time to wait for a helm Job to finish, before checking again on the next reconcile.
+const JOB_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+/// Finished Jobs are removed by the controller, the TTL only covers interrupted reconciles.
+const JOB_TTL_SECONDS: i32 = 60 * 60;
+const JOB_LOG_LINES: i64 = 100;
+const JOB_NAME_LABEL: &str = "batch.kubernetes.io/job-name";
+
+/// Runs helm commands, either in the controller container or as Kubernetes Jobs.
+#[derive(Clone, Default)]
+pub enum HelmRunner {
+    /// Runs helm as a process in the controller container.
+    #[default]
+    Process,
+
+    /// Runs helm in a Kubernetes Job with a dedicated `ServiceAccount`.
+    Job(Box<JobRunner>),
+
+    /// Records the helm command without running it, in the dry-run mode.
+    DryRun(DryRun),
+}
+
+/// Configuration for helm operations run as Kubernetes Jobs.
+#[derive(Clone)]
+pub struct JobRunner {
+    pub client: Client,
+    pub namespace: String,
+    pub service_account: String,
+    pub image: String,
+    /// Owner of the created Jobs, removing leftover Jobs with it.
+    pub owner: Option<OwnerReference>,
+}
+
+/// Result of a helm operation.
+#[derive(Clone, Debug, Default)]
+pub struct HelmOutput {
+    /// Helm command arguments.
+    pub args: Vec<String>,
+    /// Name of the Job running the operation.
+    pub job: Option<String>,
+    pub success: bool,
+    /// Output of the operation. Only the tail is kept for Jobs.
+    pub logs: String,
+}
+
+impl HelmRunner {
+    /// Runs the helm command until completion.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the helm process or Job can't be run or observed.
+    pub async fn run(&self, mut command: Command) -> HelmRunResult<HelmOutput> {
+        let args: Vec<String> = command
+            .as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+
+        match self {
+            HelmRunner::Process => {
+                let output = command
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .output()
+                    .await?;
+
+                let logs = format!(
+                    "{}{}",
+                    String::from_utf8(output.stdout)?,
+                    String::from_utf8(output.stderr)?
+                );
+                debug!("helm {}: {logs}", args.join(" "));
+                Ok(HelmOutput {
+                    args,
+                    job: None,
+                    success: output.status.success(),
+                    logs,
+                })
+            }
+            HelmRunner::Job(runner) => runner.run(args).await,
+            HelmRunner::DryRun(dry_run) => {
+                dry_run.helm(&args).await;
+                Ok(HelmOutput {
+                    args,
+                    job: None,
+                    success: true,
+                    logs: String::new(),
+                })
+            }
+        }
+    }
+
+    /// Chart reference arguments. Jobs start without the fleet repository configured,
+    /// so the repository URL is passed with the chart name.
+    pub fn chart_ref(&self, repo: &str, chart: &str) -> Vec<String> {
+        match self {
+            HelmRunner::Process | HelmRunner::DryRun(_) => vec![format!("fleet/{chart}")],
+            HelmRunner::Job(_) => vec![chart.into(), "--repo".into(), repo.into()],
+        }
+    }
+}
+
+impl JobRunner {
+    /// Runs helm in a Job, waits for it to finish and collects the logs.
+    ///
+    /// The Job name is derived from the arguments, so a Job left behind by an interrupted
+    /// reconcile is awaited instead of running the operation twice.
+    async fn run(&self, args: Vec<String>) -> HelmRunResult<HelmOutput> {
+        let name = format!("fleet-helm-{}", short_hash(&args.join(" ")));
+        let api = Api::<Job>::namespaced(self.client.clone(), &self.namespace);
+
+        if api
+            .get_opt(&name)
+            .await
+            .map_err(HelmRunError::Job)?
+            .is_none()
+        {
+            info!(
+                "Running helm {} in Job {}/{name}",
+                args.join(" "),
+                self.namespace
+            );
+            api.create(&PostParams::default(), &self.job(&name, &args))
+                .await
+                .map_err(HelmRunError::Job)?;
+        }
+
+        let job = tokio::time::timeout(JOB_TIMEOUT, await_condition(api.clone(), &name, finished))
+            .await
+            .map_err(|_| HelmRunError::Timeout(name.clone()))??;
+        let success = job
+            .and_then(|job| job.status)
+            .and_then(|status| status.succeeded)
+            .unwrap_or_default()
+            > 0;
+
+        let logs = self.logs(&name).await?;
+        api.delete(&name, &DeleteParams::background())
+            .await
+            .map_err(HelmRunError::Job)?;
+
+        Ok(HelmOutput {
+            args,
+            job: Some(name),
+            success,
+            logs,
+        })
+    }
+
+    fn job(&self, name: &str, args: &[String]) -> Job {
+        Job {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                namespace: Some(self.namespace.clone()),
+                owner_references: self.owner.clone().map(|owner| vec![owner]),
+                ..Default::default()
+            },
+            spec: Some(JobSpec {
+                backoff_limit: Some(0),
+                ttl_seconds_after_finished: Some(JOB_TTL_SECONDS),
+                template: PodTemplateSpec {
+                    metadata: None,
+                    spec: Some(PodSpec {
+                        service_account_name: Some(self.service_account.clone()),
+                        restart_policy: Some("Never".into()),
+                        containers: vec![Container {
+                            name: "helm".into(),
+                            image: Some(self.image.clone()),
+                            command: Some(vec!["helm".into()]),
+                            args: Some(args.to_vec()),
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    }),
+                },
+                ..Default::default()
+            }),
+            status: None,
+        }
+    }
+
+    async fn logs(&self, name: &str) -> HelmRunResult<String> {
+        let pods = Api::<Pod>::namespaced(self.client.clone(), &self.namespace);
+        let selector = format!("{JOB_NAME_LABEL}={name}");
+        let Some(pod) = pods
+            .list(&ListParams::default().labels(&selector))
+            .await
+            .map_err(HelmRunError::Logs)?
+            .items
+            .into_iter()
+            .next()
+        else {
+            return Ok(String::new());
+        };
+
+        pods.logs(
+            &pod.metadata.name.unwrap_or_default(),
+            &LogParams {
+                tail_lines: Some(JOB_LOG_LINES),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(HelmRunError::Logs)
+    }
+}
+
+/// Job finished either successfully or with a failure.
+fn finished(job: Option<&Job>) -> bool {
+    job.and_then(|job| job.status.as_ref())
+        .and_then(|status| status.conditions.as_ref())
+        .is_some_and(|conditions| {
+            conditions
+                .iter()
+                .any(|c| (c.type_ == "Complete" || c.type_ == "Failed") && c.status == "True")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::api::batch::v1::{Job, JobCondition, JobStatus};
+
+    use super::finished;
+
+    fn job(conditions: &[(&str, &str)]) -> Job {
+        Job {
+            status: Some(JobStatus {
+                conditions: Some(
+                    conditions
+                        .iter()
+                        .map(|(type_, status)| JobCondition {
+                            type_: (*type_).into(),
+                            status: (*status).into(),
+                            ..Default::default()
+                        })
+                        .collect(),
+                ),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_finished() {
+        assert!(!finished(None));
+        assert!(!finished(Some(&Job::default())));
+        assert!(!finished(Some(&job(&[("Suspended", "True")]))));
+        assert!(!finished(Some(&job(&[("Complete", "False")]))));
+        assert!(finished(Some(&job(&[("Complete", "True")]))));
+        assert!(finished(Some(&job(&[
+            ("FailureTarget", "True"),
+            ("Failed", "True")
+        ]))));
+    }
+}
@@ -0,0 +1,165 @@
+use k8s_openapi::apimachinery::pkg::version::Info;
+use semver::{Version, VersionReq};
+
+use super::{VersionResolveError, VersionResolveResult};
+
+const OPERATORS: &[char] = &['=', '!', '<', '>', '~', '^'];
+
+/// Parses a helm style semver constraint, like `~0.12` or `>=0.11 <0.13 || 0.14.x`.
+///
+/// Comparators may be separated by spaces or commas, alternatives by `||`.
+/// A version without an operator matches that version exactly, as in helm.
+pub(crate) fn parse_constraint(constraint: &str) -> VersionResolveResult<Vec<VersionReq>> {
+    let requirements: Vec<VersionReq> = constraint
+        .split("||")
+        .map(|alternative| {
+            let mut comparators: Vec<String> = vec![];
+            let mut operator = String::new();
+            for token in alternative.split([' ', ',']).filter(|t| !t.is_empty()) {
+                let version = token.trim_start_matches(OPERATORS);
+                operator.push_str(&token[..token.len() - version.len()]);
+                if version.is_empty() {
+                    continue;
+                }
+
+                let version = version.strip_prefix('v').unwrap_or(version);
+                let operator = match std::mem::take(&mut operator) {
+                    op if op.is_empty() && !version.contains(['x', 'X', '*']) => "=".to_string(),
+                    op => op,
+                };
+                comparators.push(format!("{operator}{version}"));
+            }
+
+            VersionReq::parse(&comparators.join(", "))
+                .map_err(|e| VersionResolveError::Parse(constraint.to_string(), e))
+        })
+        .collect::<VersionResolveResult<_>>()?;
+
+    Ok(requirements)
+}
+
+/// Checks if the version satisfies the helm style semver constraint.
+pub(crate) fn satisfies(constraint: &str, version: &Version) -> VersionResolveResult<bool> {
+    Ok(parse_constraint(constraint)?
+        .iter()
+        .any(|req| req.matches(version)))
+}
+
+/// Parses the Kubernetes version reported by the API server `/version` endpoint.
+///
+/// The full `gitVersion` is used when it is valid semver, keeping the patch, pre-release and
+/// build metadata, like `v1.31.2-eks-7f9249a` or `v1.30.4+k3s1`. Otherwise the version falls back
+/// to the `major` and `minor` fields, ignoring non numeric suffixes, like `27+` on GKE.
+pub fn server_version(info: &Info) -> Option<Version> {
+    let git_version = info.git_version.as_str();
+    if let Ok(version) = Version::parse(git_version.strip_prefix('v').unwrap_or(git_version)) {
+        return Some(version);
+    }
+
+    let number = |field: &str| {
+        let digits = field.trim_end_matches(|c: char| !c.is_ascii_digit());
+        digits.parse::<u64>().ok()
+    };
+    Some(Version::new(number(&info.major)?, number(&info.minor)?, 0))
+}
+
+/// Picks the highest of the available versions satisfying the constraint.
+///
+/// Pre-release versions are only selected if the constraint explicitly references them.
+pub(crate) fn resolve<'a>(
+    constraint: &str,
+    available: impl IntoIterator<Item = &'a str>,
+) -> VersionResolveResult<Version> {
+    let requirements = parse_constraint(constraint)?;
+
+    available
+        .into_iter()
+        .filter_map(|version| Version::parse(version.strip_prefix('v').unwrap_or(version)).ok())
+        .filter(|version| requirements.iter().any(|req| req.matches(version)))
+        .max()
+        .ok_or_else(|| VersionResolveError::NoMatch(constraint.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::apimachinery::pkg::version::Info;
+
+    use crate::controllers::helm::VersionResolveError;
+
+    use super::{resolve, server_version};
+
+    const AVAILABLE: &[&str] = &[
+        "0.11.0",
+        "0.11.9",
+        "0.12.0",
+        "0.12.5",
+        "0.13.0-rc.1",
+        "0.13.1",
+        "1.0.0",
+    ];
+
+    #[test]
+    fn test_resolve() {
+        for (constraint, expected) in [
+            ("~0.12", "0.12.5"),
+            ("~0.12.1", "0.12.5"),
+            ("^0.11", "0.11.9"),
+            (">=0.11 <0.13", "0.12.5"),
+            (">= 0.11, < 0.13", "0.12.5"),
+            ("0.12.0", "0.12.0"),
+            ("v0.12.0", "0.12.0"),
+            ("0.12.x", "0.12.5"),
+            ("<0.12 || >=0.13 <1", "0.13.1"),
+            (">=0.13.0-rc.1 <0.13.1", "0.13.0-rc.1"),
+            (">=0.12", "1.0.0"),
+        ] {
+            assert_eq!(
+                expected,
+                resolve(constraint, AVAILABLE.iter().copied())
+                    .unwrap()
+                    .to_string(),
+                "constraint {constraint}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_invalid() {
+        assert!(matches!(
+            resolve("~0.14", AVAILABLE.iter().copied()),
+            Err(VersionResolveError::NoMatch(_))
+        ));
+        assert!(matches!(
+            resolve("latest", AVAILABLE.iter().copied()),
+            Err(VersionResolveError::Parse(..))
+        ));
+    }
+
+    #[test]
+    fn test_server_version() {
+        let info = |git_version: &str, major: &str, minor: &str| Info {
+            git_version: git_version.into(),
+            major: major.into(),
+            minor: minor.into(),
+            ..Default::default()
+        };
+
+        for (git_version, minor, expected) in [
+            ("v1.31.2", "31", "1.31.2"),
+            ("v1.30.4+k3s1", "30", "1.30.4+k3s1"),
+            ("v1.32.0-rc.1", "32", "1.32.0-rc.1"),
+            ("v1.29.8-eks-a737599", "29+", "1.29.8-eks-a737599"),
+            ("unknown", "27+", "1.27.0"),
+        ] {
+            assert_eq!(
+                expected,
+                server_version(&info(git_version, "1", minor))
+                    .unwrap()
+                    .to_string(),
+                "gitVersion {git_version}"
+            );
+        }
+
+        assert!(server_version(&info("", "", "")).is_none());
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use jiff::Timestamp;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use kube::api::{Patch, PatchParams};
+use kube::runtime::controller::Action;
+use kube::runtime::reflector::{ObjectRef, Store};
+use kube::{Api, Resource as _};
+use serde_json::json;
+use tracing::warn;
+
+use crate::api::capi_cluster::Cluster;
+use crate::api::fleet_addon_config::{FleetAddonConfig, ImportFailure, ImportProgressSummary};
+
+use super::controller::Context;
+
+/// Interval between the import progress status updates.
+const REPORT_INTERVAL: Duration = Duration::from_secs(30);
+/// Number of import failures kept in the summary.
+const FAILURE_LIMIT: usize = 10;
+/// Size limit of a failure message.
+const MESSAGE_LIMIT: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ImportState {
+    Imported,
+    Failed,
+}
+
+#[derive(Default)]
+struct Progress {
+    states: HashMap<ObjectRef<Cluster>, ImportState>,
+    failures: VecDeque<ImportFailure>,
+    /// Clusters reconciled without the kubeconfig secret, until the reconcile is observed
+    kubeconfig_missing: HashSet<ObjectRef<Cluster>>,
+}
+
+/// Import outcome of the reconciled clusters, shared between the cluster reconcilers.
+#[derive(Clone, Default)]
+pub struct ImportProgress {
+    progress: Arc<RwLock<Progress>>,
+}
+
+impl ImportProgress {
+    /// Marks the cluster as waiting for the kubeconfig secret, keeping it pending
+    /// once the reconcile outcome is observed.
+    pub(crate) fn kubeconfig_missing(&self, cluster: &Cluster) {
+        if let Ok(mut progress) = self.progress.write() {
+            progress
+                .kubeconfig_missing
+                .insert(ObjectRef::from_obj(cluster));
+        }
+    }
+
+    /// Records the outcome of a cluster reconcile.
+    pub(crate) fn observe(&self, cluster: &Cluster, result: &crate::Result<Action>) {
+        let Ok(mut progress) = self.progress.write() else {
+            return;
+        };
+
+        let key = ObjectRef::from_obj(cluster);
+        let kubeconfig_missing = progress.kubeconfig_missing.remove(&key);
+        match result {
+            _ if cluster.meta().deletion_timestamp.is_some() => {
+                progress.states.remove(&key);
+            }
+            Err(e) => {
+                progress.states.insert(key, ImportState::Failed);
+
+                let cluster = cluster.object_ref(&());
+                let mut message = e.to_string();
+                if message.len() > MESSAGE_LIMIT {
+                    let mut end = MESSAGE_LIMIT;
+                    while !message.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    message.truncate(end);
+                    message.push_str("...");
+                }
+
+                progress.failures.retain(|f| f.cluster != cluster);
+                progress.failures.push_front(ImportFailure {
+                    cluster,
+                    message,
+                    time: Time(Timestamp::now()),
+                });
+                progress.failures.truncate(FAILURE_LIMIT);
+            }
+            Ok(_) if !kubeconfig_missing && cluster.cluster_ready() == Some(true) => {
+                progress.states.insert(key, ImportState::Imported);
+            }
+            Ok(_) => {
+                progress.states.remove(&key);
+            }
+        }
+    }
+
+    /// Summarizes the import progress of the clusters in the store.
+    ///
+    /// Clusters no longer matched by the selectors are dropped from the progress.
+    pub(crate) fn summary(&self, clusters: &Store<Cluster>) -> ImportProgressSummary {
+        let Ok(mut progress) = self.progress.write() else {
+            return ImportProgressSummary::default();
+        };
+
+        progress.states.retain(|key, _| clusters.get(key).is_some());
+        let count = |state| progress.states.values().filter(|s| **s == state).count();
+        let matched = clusters.state().len();
+        let imported = count(ImportState::Imported);
+        let failed = count(ImportState::Failed);
+
+        let to_i64 = |count: usize| i64::try_from(count).unwrap_or(i64::MAX);
+        ImportProgressSummary {
+            matched: to_i64(matched),
+            imported: to_i64(imported),
+            pending: to_i64(matched.saturating_sub(imported + failed)),
+            failed: to_i64(failed),
+            recent_failures: progress.failures.iter().cloned().collect(),
+        }
+    }
+}
+
+/// Periodically writes the import progress to the `FleetAddonConfig` status,
+/// when it differs from the last written one.
+pub(crate) async fn report(ctx: Arc<Context>, clusters: Store<Cluster>) {
+    let api = Api::<FleetAddonConfig>::all(ctx.client.clone());
+    let mut interval = tokio::time::interval(REPORT_INTERVAL);
+    let mut reported = None;
+
+    loop {
+        interval.tick().await;
+
+        let summary = ctx.imports.summary(&clusters);
+        if reported.as_ref() == Some(&summary) || ctx.flags.dry_run {
+            continue;
+        }
+
+        let patch = json!({
+            "status": {
+                "importProgress": summary,
+            },
+        });
+        match api
+            .patch_status(
+                "fleet-addon-config",
+                &PatchParams::default(),
+                &Patch::Merge(&patch),
+            )
+            .await
+        {
+            Ok(_) => reported = Some(summary),
+            Err(e) => warn!("Failed to update import progress: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use cluster_api_rs::capi_cluster::{ClusterSpec, ClusterStatus, ClusterStatusInitialization};
+    use kube::runtime::controller::Action;
+    use kube::runtime::{reflector, watcher::Event};
+
+    use crate::Error;
+    use crate::api::capi_cluster::{Cluster, ClusterProxy};
+
+    use super::ImportProgress;
+
+    fn cluster(name: &str) -> Cluster {
+        let mut cluster = Cluster::new(
+            name,
+            ClusterProxy {
+                proxy: ClusterSpec::default(),
+            },
+        );
+        cluster.metadata.namespace = Some("default".into());
+        cluster
+    }
+
+    #[test]
+    fn test_summary() {
+        let (reader, mut writer) = reflector::store();
+        let progress = ImportProgress::default();
+
+        let failed = Err(Error::CommandError(io::Error::other("import failed")));
+        let (a, b, c) = (cluster("a"), cluster("b"), cluster("c"));
+        for cluster in [&a, &b, &c] {
+            writer.apply_watcher_event(&Event::Apply(cluster.clone()));
+        }
+
+        progress.observe(&a, &failed);
+        progress.observe(&b, &failed);
+        progress.observe(&a, &failed);
+        progress.observe(&c, &Ok(Action::await_change()));
+
+        let summary = progress.summary(&reader);
+        assert_eq!(
+            (3, 0, 1, 2),
+            (
+                summary.matched,
+                summary.imported,
+                summary.pending,
+                summary.failed
+            )
+        );
+        let failed: Vec<_> = summary
+            .recent_failures
+            .iter()
+            .map(|f| f.cluster.name.as_deref().unwrap_or_default())
+            .collect();
+        assert_eq!(vec!["a", "b"], failed);
+
+        // Unmatched clusters are dropped
+        writer.apply_watcher_event(&Event::Delete(a));
+        let summary = progress.summary(&reader);
+        assert_eq!(
+            (2, 1, 1),
+            (summary.matched, summary.pending, summary.failed)
+        );
+    }
+
+    #[test]
+    fn test_summary_kubeconfig_missing() {
+        let (reader, mut writer) = reflector::store();
+        let progress = ImportProgress::default();
+
+        let mut ready = cluster("a");
+        ready.status = Some(ClusterStatus {
+            initialization: Some(ClusterStatusInitialization {
+                control_plane_initialized: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        writer.apply_watcher_event(&Event::Apply(ready.clone()));
+
+        // Clusters without the kubeconfig secret are pending
+        progress.kubeconfig_missing(&ready);
+        progress.observe(&ready, &Ok(Action::await_change()));
+        let summary = progress.summary(&reader);
+        assert_eq!((1, 0), (summary.pending, summary.imported));
+
+        progress.observe(&ready, &Ok(Action::await_change()));
+        let summary = progress.summary(&reader);
+        assert_eq!((0, 1), (summary.pending, summary.imported));
+    }
+}
@@ -0,0 +1,308 @@
+use crate::api::comparable::ResourceDiff as _;
+use crate::api::fleet_addon_config::{FleetAddonConfig, LabelGroup};
+use crate::api::fleet_cluster::source_cluster_ref;
+use crate::api::fleet_clustergroup::ClusterGroup;
+use crate::telemetry;
+
+use kube::ResourceExt;
+use kube::api::{Api, PatchParams};
+use kube::runtime::controller::Action;
+use tracing::{Span, debug, field::display, info, instrument};
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use super::controller::{Context, delete, patch};
+use super::{LabelGroupSyncError, LabelGroupSyncResult};
+
+impl FleetAddonConfig {
+    /// Reconciles `ClusterGroups` generated for distinct label values of imported clusters,
+    /// and version-aware `ClusterClass` groups.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the generated groups cannot be synced.
+    #[instrument(skip_all, fields(reconcile_id, name = self.name_any()), err)]
+    pub async fn reconcile_label_groups(
+        self: Arc<Self>,
+        ctx: Arc<Context>,
+    ) -> crate::Result<Action> {
+        let _current = Span::current().record("reconcile_id", display(telemetry::get_trace_id()));
+        Ok(self.sync_label_groups(ctx).await?)
+    }
+
+    async fn sync_label_groups(&self, ctx: Arc<Context>) -> LabelGroupSyncResult<Action> {
+        let generators = match self.spec.cluster.as_ref() {
+            Some(cluster) => cluster.label_groups(),
+            None => vec![],
+        };
+
+        let version_groups = self.cluster_class_version_groups_enabled();
+
+        let mut desired: BTreeMap<(String, String), ClusterGroup> = BTreeMap::new();
+        if !generators.is_empty() || version_groups {
+            let clusters = ctx
+                .fleet_clusters()
+                .await
+                .map_err(LabelGroupSyncError::ClusterLookup)?;
+
+            // Only clusters imported by the addon provider are considered
+            let imported: Vec<_> = clusters
+                .iter()
+                .filter(|cluster| source_cluster_ref(cluster.as_ref()).is_some())
+                .collect();
+            desired = label_groups(
+                &generators,
+                imported
+                    .iter()
+                    .map(|cluster| (cluster.namespace().unwrap_or_default(), cluster.labels())),
+            );
+
+            for cluster in imported {
+                let namespace = cluster.namespace().unwrap_or_default();
+                if version_groups
+                    && let Some(group) =
+                        ClusterGroup::for_class_version(cluster.labels(), &namespace)
+                {
+                    desired.insert((namespace.clone(), group.name_any()), group);
+                }
+            }
+        }
+
+        let existing = ctx
+            .label_groups()
+            .await
+            .map_err(LabelGroupSyncError::GroupLookup)?;
+
+        for group in desired.values_mut() {
+            // Groups observed in the desired state are not patched again
+            let current = existing.iter().find(|existing| {
+                existing.namespace() == group.namespace() && existing.name_any() == group.name_any()
+            });
+            if current.is_some_and(|current| {
+                !group.diff(current) && group.spec.selector == current.spec.selector
+            }) {
+                continue;
+            }
+
+            patch(
+                ctx.clone(),
+                group,
+                &PatchParams::apply("addon-provider-fleet"),
+            )
+            .await?;
+        }
+
+        for group in stale_groups(&existing, &desired) {
+            let namespace = group.namespace().unwrap_or_default();
+            let api = Api::<ClusterGroup>::namespaced(ctx.client.clone(), &namespace);
+            delete(ctx.clone(), &api, group.as_ref())
+                .await
+                .map_err(LabelGroupSyncError::Delete)?;
+            info!(
+                "Removed label group {} in {namespace}, as the label value is no longer present",
+                group.name_any()
+            );
+        }
+
+        debug!("Reconciled {} label groups", desired.len());
+        Ok(Action::await_change())
+    }
+}
+
+/// Groups for the distinct label values of the clusters, keyed by namespace and name.
+///
+/// Distinct label values sharing a group name, like `a/env: dev` and `b/env: dev` without
+/// a prefix, get names suffixed with a hash of the label and the value instead, regardless
+/// of the cluster order.
+fn label_groups<'a>(
+    generators: &[LabelGroup],
+    clusters: impl IntoIterator<Item = (String, &'a BTreeMap<String, String>)>,
+) -> BTreeMap<(String, String), ClusterGroup> {
+    let mut values = BTreeSet::new();
+    for (namespace, labels) in clusters {
+        for (index, generator) in generators.iter().enumerate() {
+            if let Some(value) = labels.get(&generator.label) {
+                values.insert((namespace.clone(), index, value.as_str()));
+            }
+        }
+    }
+
+    let mut selectors: BTreeMap<(&str, String), BTreeSet<(&str, &str)>> = BTreeMap::new();
+    for (namespace, index, value) in &values {
+        let generator = &generators[*index];
+        selectors
+            .entry((namespace.as_str(), generator.group_name(value)))
+            .or_default()
+            .insert((generator.label.as_str(), value));
+    }
+
+    values
+        .iter()
+        .map(|(namespace, index, value)| {
+            let generator = &generators[*index];
+            let name = generator.group_name(value);
+            let name = match selectors.get(&(namespace.as_str(), name.clone())) {
+                Some(selectors) if selectors.len() > 1 => generator.unique_group_name(value),
+                _ => name,
+            };
+            let group = ClusterGroup::for_label_value(generator, value, name.clone(), namespace);
+            ((namespace.clone(), name), group)
+        })
+        .collect()
+}
+
+/// Existing groups no longer desired, keyed by namespace and name.
+fn stale_groups<'a>(
+    existing: &'a [Arc<ClusterGroup>],
+    desired: &BTreeMap<(String, String), ClusterGroup>,
+) -> impl Iterator<Item = &'a Arc<ClusterGroup>> {
+    existing.iter().filter(|group| {
+        !desired.contains_key(&(group.namespace().unwrap_or_default(), group.name_any()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use kube::ResourceExt as _;
+    use kube::api::ObjectMeta;
+
+    use crate::api::fleet_addon_config::LabelGroup;
+    use crate::api::fleet_clustergroup::ClusterGroup;
+    use crate::controllers::dry_run::{DryRun, DryRunOperation};
+
+    use super::{label_groups, stale_groups};
+
+    fn group(namespace: &str, name: &str) -> ClusterGroup {
+        ClusterGroup {
+            metadata: ObjectMeta {
+                name: Some(name.into()),
+                namespace: Some(namespace.into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stale_groups_dry_run_delete() {
+        let existing = vec![
+            Arc::new(group("default", "env-dev")),
+            Arc::new(group("default", "env-prod")),
+            Arc::new(group("other", "env-dev")),
+        ];
+        let desired = BTreeMap::from([(
+            ("default".to_string(), "env-dev".to_string()),
+            group("default", "env-dev"),
+        )]);
+
+        let stale: Vec<_> = stale_groups(&existing, &desired).collect();
+        assert_eq!(
+            vec![
+                (Some("default".to_string()), "env-prod".to_string()),
+                (Some("other".to_string()), "env-dev".to_string()),
+            ],
+            stale
+                .iter()
+                .map(|group| (group.namespace(), group.name_any()))
+                .collect::<Vec<_>>()
+        );
+
+        // Stale groups are recorded instead of removed in the dry-run mode
+        let dry_run = DryRun::default();
+        for group in stale {
+            dry_run.delete(group.as_ref()).await;
+        }
+        let diffs = dry_run.diffs().await;
+        assert_eq!(2, diffs.len());
+        assert!(
+            diffs.iter().all(
+                |diff| diff.operation == DryRunOperation::Delete && diff.kind == "ClusterGroup"
+            )
+        );
+    }
+
+    #[test]
+    fn test_label_groups_collisions() {
+        let generators = vec![
+            LabelGroup {
+                label: "env".into(),
+                prefix: None,
+            },
+            LabelGroup {
+                label: "a.example.com/tier".into(),
+                prefix: None,
+            },
+            LabelGroup {
+                label: "b.example.com/tier".into(),
+                prefix: None,
+            },
+        ];
+        let labels = |labels: &[(&str, &str)]| {
+            labels
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let clusters = [
+            labels(&[("env", "Prod")]),
+            labels(&[("env", "prod")]),
+            labels(&[("env", "dev"), ("a.example.com/tier", "web")]),
+            labels(&[("b.example.com/tier", "web")]),
+        ];
+
+        let groups = |clusters: &[BTreeMap<String, String>]| {
+            label_groups(
+                &generators,
+                clusters
+                    .iter()
+                    .map(|labels| ("default".to_string(), labels)),
+            )
+        };
+        let desired = groups(&clusters);
+
+        // Every distinct label value gets its own group
+        assert_eq!(5, desired.len());
+        let selectors: Vec<_> = desired
+            .values()
+            .map(|group| {
+                group
+                    .spec
+                    .selector
+                    .as_ref()
+                    .and_then(|selector| selector.match_labels.clone())
+                    .unwrap_or_default()
+            })
+            .collect();
+        for (label, value) in [
+            ("env", "Prod"),
+            ("env", "prod"),
+            ("env", "dev"),
+            ("a.example.com/tier", "web"),
+            ("b.example.com/tier", "web"),
+        ] {
+            assert_eq!(
+                1,
+                selectors
+                    .iter()
+                    .filter(|selector| selector.get(label).map(String::as_str) == Some(value))
+                    .count(),
+                "{label}={value}"
+            );
+        }
+        assert!(desired.contains_key(&("default".to_string(), "env-prod".to_string())));
+        assert!(desired.contains_key(&("default".to_string(), "env-dev".to_string())));
+        assert!(!desired.contains_key(&("default".to_string(), "tier-web".to_string())));
+
+        // Names don't depend on the cluster order
+        let mut reversed = clusters.to_vec();
+        reversed.reverse();
+        assert_eq!(
+            desired.keys().collect::<Vec<_>>(),
+            groups(&reversed).keys().collect::<Vec<_>>()
+        );
+    }
+}
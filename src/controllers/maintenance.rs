@@ -0,0 +1,306 @@
+use std::str::FromStr;
+
+use jiff::{SignedDuration, Timestamp, ToSpan as _, civil::DateTime, tz::TimeZone};
+
+use crate::api::fleet_addon_config::MaintenanceWindow;
+
+use super::{MaintenanceWindowError, MaintenanceWindowResult};
+
+/// Window starts are only searched within this many years ahead.
+const SEARCH_HORIZON_YEARS: i16 = 8;
+
+/// Returns the start of the next maintenance window, or `None` if a window is currently open.
+///
+/// With no windows configured, upgrades are always allowed.
+pub(crate) fn next_window(
+    windows: &[MaintenanceWindow],
+    now: Timestamp,
+) -> MaintenanceWindowResult<Option<Timestamp>> {
+    let mut next: Option<Timestamp> = None;
+    for window in windows {
+        let schedule = Schedule::from_str(&window.schedule)?;
+        let duration = SignedDuration::from_str(&window.duration).map_err(|e| {
+            MaintenanceWindowError::Duration(window.duration.clone(), e.to_string())
+        })?;
+        let time_zone = window.time_zone.as_deref().unwrap_or("UTC");
+        let tz = TimeZone::get(time_zone)
+            .map_err(|e| MaintenanceWindowError::TimeZone(time_zone.into(), e.to_string()))?;
+
+        // The earliest start of a window still open at `now`, or the next window start.
+        let after = now.checked_sub(duration).map_err(|e| {
+            MaintenanceWindowError::Duration(window.duration.clone(), e.to_string())
+        })?;
+        let start = schedule
+            .next_after(after, &tz)
+            .ok_or_else(|| MaintenanceWindowError::NoOccurrence(window.schedule.clone()))?;
+        if start <= now {
+            return Ok(None);
+        }
+
+        next = Some(next.map_or(start, |next| next.min(start)));
+    }
+
+    Ok(next)
+}
+
+/// Cron schedule in the standard 5 field format: minute, hour, day of month, month and day of week.
+#[derive(Debug, PartialEq)]
+struct Schedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl FromStr for Schedule {
+    type Err = MaintenanceWindowError;
+
+    fn from_str(schedule: &str) -> Result<Self, Self::Err> {
+        let expanded = match schedule.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            schedule => schedule,
+        };
+        let invalid =
+            |reason: &str| MaintenanceWindowError::Schedule(schedule.into(), reason.into());
+
+        let [minutes, hours, days, months, weekdays] = expanded
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .try_into()
+            .map_err(|_| invalid("expected 5 fields"))?;
+
+        let mut weekday_bits = field(weekdays, 0, 7, WEEKDAYS).map_err(|e| invalid(&e))?;
+        // Both 0 and 7 are Sunday
+        if weekday_bits & (1 << 7) != 0 {
+            weekday_bits |= 1;
+        }
+
+        Ok(Self {
+            minutes: field(minutes, 0, 59, &[]).map_err(|e| invalid(&e))?,
+            hours: field(hours, 0, 23, &[]).map_err(|e| invalid(&e))?,
+            days: field(days, 1, 31, &[]).map_err(|e| invalid(&e))?,
+            months: field(months, 1, 12, MONTHS).map_err(|e| invalid(&e))?,
+            weekdays: weekday_bits,
+            any_day: days == "*",
+            any_weekday: weekdays == "*",
+        })
+    }
+}
+
+const MONTHS: &[&str] = &[
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAYS: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// Parses a cron field into a bit set of the matching values.
+fn field(field: &str, min: u8, max: u8, names: &[&str]) -> Result<u64, String> {
+    let value = |v: &str| -> Result<u8, String> {
+        let parsed = match names.iter().position(|n| n.eq_ignore_ascii_case(v)) {
+            Some(i) => u8::try_from(i).map_err(|e| e.to_string())? + min,
+            None => v.parse().map_err(|_| format!("invalid value `{v}`"))?,
+        };
+        if parsed < min || parsed > max {
+            return Err(format!("value `{v}` out of range {min}-{max}"));
+        }
+        Ok(parsed)
+    };
+
+    let mut bits = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse().map_err(|_| format!("invalid step `{step}`"))?,
+            ),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err("step must be positive".into());
+        }
+
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (value(start)?, value(end)?),
+            // `a/n` continues to the end of the range
+            None if part.contains('/') => (value(range)?, max),
+            None => (value(range)?, value(range)?),
+        };
+        if start > end {
+            return Err(format!("invalid range `{range}`"));
+        }
+
+        for v in (start..=end).step_by(step) {
+            bits |= 1 << v;
+        }
+    }
+
+    Ok(bits)
+}
+
+impl Schedule {
+    fn matches_day(&self, dt: DateTime) -> bool {
+        let day = self.days & (1 << dt.day()) != 0;
+        let weekday = self.weekdays & (1 << dt.weekday().to_sunday_zero_offset()) != 0;
+        match (self.any_day, self.any_weekday) {
+            // Restricting both matches either, as in cron
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    /// Returns the first scheduled time strictly after the given timestamp.
+    fn next_after(&self, after: Timestamp, tz: &TimeZone) -> Option<Timestamp> {
+        let start = after.to_zoned(tz.clone()).datetime();
+        let horizon = start.year() + SEARCH_HORIZON_YEARS;
+        let mut dt = start
+            .with()
+            .second(0)
+            .subsec_nanosecond(0)
+            .build()
+            .ok()?
+            .checked_add(1.minute())
+            .ok()?;
+
+        while dt.year() <= horizon {
+            dt = if self.months & (1 << dt.month()) == 0 {
+                dt.first_of_month()
+                    .start_of_day()
+                    .checked_add(1.month())
+                    .ok()?
+            } else if !self.matches_day(dt) {
+                dt.tomorrow().ok()?.start_of_day()
+            } else if self.hours & (1 << dt.hour()) == 0 {
+                dt.with()
+                    .minute(0)
+                    .build()
+                    .ok()?
+                    .checked_add(1.hour())
+                    .ok()?
+            } else if self.minutes & (1 << dt.minute()) == 0 {
+                dt.checked_add(1.minute()).ok()?
+            } else {
+                let next = dt.to_zoned(tz.clone()).ok()?.timestamp();
+                if next > after {
+                    return Some(next);
+                }
+                dt.checked_add(1.minute()).ok()?
+            };
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::Timestamp;
+
+    use crate::{api::fleet_addon_config::MaintenanceWindow, controllers::MaintenanceWindowError};
+
+    use super::next_window;
+
+    fn window(schedule: &str, duration: &str, time_zone: Option<&str>) -> MaintenanceWindow {
+        MaintenanceWindow {
+            schedule: schedule.into(),
+            duration: duration.into(),
+            time_zone: time_zone.map(Into::into),
+        }
+    }
+
+    fn at(timestamp: &str) -> Timestamp {
+        timestamp.parse().unwrap()
+    }
+
+    #[test]
+    fn test_next_window() {
+        // Saturday
+        let now = at("2026-10-17T12:30:00Z");
+        for (windows, expected) in [
+            (vec![], None),
+            (vec![window("0 12 * * *", "1h", None)], None),
+            (
+                vec![window("0 12 * * *", "30m", None)],
+                Some("2026-10-18T12:00:00Z"),
+            ),
+            (
+                vec![window("0 2 * * SUN", "2h", None)],
+                Some("2026-10-18T02:00:00Z"),
+            ),
+            (
+                vec![window("0 2 * * 1-5", "2h", None)],
+                Some("2026-10-19T02:00:00Z"),
+            ),
+            (vec![window("*/15 * * * *", "5m", None)], None),
+            (
+                vec![window("5/15 * * * *", "5m", None)],
+                Some("2026-10-17T12:35:00Z"),
+            ),
+            (
+                vec![window("0 0 1 JAN *", "24h", None)],
+                Some("2027-01-01T00:00:00Z"),
+            ),
+            (
+                vec![window("0 0 29 2 *", "1h", None)],
+                Some("2028-02-29T00:00:00Z"),
+            ),
+            (
+                vec![window("0 2 * * *", "2h", Some("Europe/Berlin"))],
+                Some("2026-10-18T00:00:00Z"),
+            ),
+            (
+                vec![
+                    window("0 2 * * *", "2h", None),
+                    window("0 20 * * *", "2h", None),
+                ],
+                Some("2026-10-17T20:00:00Z"),
+            ),
+            (
+                vec![
+                    window("0 2 * * *", "2h", None),
+                    window("@hourly", "45m", None),
+                ],
+                None,
+            ),
+        ] {
+            assert_eq!(
+                expected.map(at),
+                next_window(&windows, now).unwrap(),
+                "windows {windows:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_next_window_invalid() {
+        let now = Timestamp::now();
+        for (window, matcher) in [
+            (
+                window("0 2 * *", "1h", None),
+                (|e| matches!(e, MaintenanceWindowError::Schedule(..)))
+                    as fn(&MaintenanceWindowError) -> bool,
+            ),
+            (window("60 2 * * *", "1h", None), |e| {
+                matches!(e, MaintenanceWindowError::Schedule(..))
+            }),
+            (window("0 2 * * *", "soon", None), |e| {
+                matches!(e, MaintenanceWindowError::Duration(..))
+            }),
+            (window("0 2 * * *", "1h", Some("Mars/Olympus")), |e| {
+                matches!(e, MaintenanceWindowError::TimeZone(..))
+            }),
+            (window("0 0 31 2 *", "1h", None), |e| {
+                matches!(e, MaintenanceWindowError::NoOccurrence(..))
+            }),
+        ] {
+            let err = next_window(std::slice::from_ref(&window), now).unwrap_err();
+            assert!(matcher(&err), "window {window:?}: {err}");
+        }
+    }
+}
@@ -42,6 +42,30 @@ pub enum ClusterSyncError {
 
     #[error("Cluster json encoding error: {0}")]
     ClusterEncodeError(#[from] serde_json::Error),
+
+    #[error("Fleet cluster name lookup error: {0}")]
+    NameLookupError(#[source] kube::Error),
+
+    #[error("Fleet cluster name `{name}` is already used by cluster `{source_cluster}`")]
+    NameCollision {
+        name: String,
+        source_cluster: String,
+    },
+
+    #[error("Cluster condition update error: {0}")]
+    ConditionError(#[source] kube::Error),
+
+    #[error("Diagnostics error: {0}")]
+    Event(#[source] kube::Error),
+
+    #[error("Kubeconfig secret lookup error: {0}")]
+    KubeconfigLookupError(#[source] kube::Error),
+
+    #[error("Kubeconfig secret replica update error: {0}")]
+    KubeconfigPatchError(#[source] PatchError),
+
+    #[error("Stale Fleet cluster removal error: {0}")]
+    StaleImportRemoval(#[source] kube::Error),
 }
 
 pub type GroupSyncResult<T, E = GroupSyncError> = std::result::Result<T, E>;
@@ -56,6 +80,32 @@ pub enum GroupSyncError {
 
     #[error("Unable to find origin ClusterClass for the ClusterGroup: {0}")]
     ClassLookup(#[from] kube::Error),
+
+    #[error("ClusterGroup status lookup error: {0}")]
+    StatusLookup(#[source] kube::Error),
+
+    #[error("ClusterClass condition update error: {0}")]
+    ConditionError(#[source] kube::Error),
+
+    #[error("Diagnostics error: {0}")]
+    Event(#[source] kube::Error),
+}
+
+pub type LabelGroupSyncResult<T, E = LabelGroupSyncError> = std::result::Result<T, E>;
+
+#[derive(Error, Debug)]
+pub enum LabelGroupSyncError {
+    #[error("Imported clusters lookup error: {0}")]
+    ClusterLookup(#[source] kube::Error),
+
+    #[error("Label group lookup error: {0}")]
+    GroupLookup(#[source] kube::Error),
+
+    #[error("Label group update error: {0}")]
+    Patch(#[from] PatchError),
+
+    #[error("Label group delete error: {0}")]
+    Delete(#[source] kube::Error),
 }
 
 pub type GetOrCreateResult<T, E = GetOrCreateError> = std::result::Result<T, E>;
@@ -109,12 +159,71 @@ pub enum BundleError {
 
     #[error("BundleNamespaceMapping creating error: {0}")]
     Mapping(#[from] BundleMappingError),
+
+    #[error("Cluster condition update error: {0}")]
+    Condition(#[source] kube::Error),
+
+    #[error("Fleet cluster name lookup error: {0}")]
+    NameLookup(#[source] kube::Error),
 }
 
 #[derive(Error, Debug)]
 pub enum BundleMappingError {
     #[error("ClusterClass lookup error: {0}")]
     ClusterClassLookup(#[from] kube::Error),
+
+    #[error("ClusterClass bundle selector parse error: {0}")]
+    BundleSelector(#[from] serde_json::Error),
+}
+
+pub type CertificateResult<T, E = CertificateError> = std::result::Result<T, E>;
+
+#[derive(Error, Debug)]
+pub enum CertificateError {
+    #[error("No CA certificates found")]
+    Empty,
+
+    #[error("CA key `{key}` is missing in `{source_ref}`")]
+    MissingKey { key: String, source_ref: String },
+
+    #[error("CA in `{source_ref}` is not valid UTF-8")]
+    Encoding { source_ref: String },
+
+    #[error("PEM decode error: {0}")]
+    Pem(String),
+
+    #[error("Unexpected PEM block `{0}`, expected `CERTIFICATE`")]
+    UnexpectedBlock(String),
+
+    #[error("Certificate parse error: {0}")]
+    Parse(String),
+
+    #[error("Certificate `{subject}` is not valid now, validity period {not_before} - {not_after}")]
+    Validity {
+        subject: String,
+        not_before: String,
+        not_after: String,
+    },
+
+    #[error("Certificate `{subject}` is not a CA certificate")]
+    NotCA { subject: String },
+}
+
+pub type MaintenanceWindowResult<T, E = MaintenanceWindowError> = std::result::Result<T, E>;
+
+#[derive(Error, Debug)]
+pub enum MaintenanceWindowError {
+    #[error("Invalid maintenance window schedule `{0}`: {1}")]
+    Schedule(String, String),
+
+    #[error("Invalid maintenance window duration `{0}`: {1}")]
+    Duration(String, String),
+
+    #[error("Invalid maintenance window time zone `{0}`: {1}")]
+    TimeZone(String, String),
+
+    #[error("Maintenance window schedule `{0}` never occurs")]
+    NoOccurrence(String),
 }
 
 pub type ConfigFetchResult<T> = std::result::Result<T, ConfigFetchError>;
@@ -126,8 +235,15 @@ pub enum ConfigFetchError {
 }
 
 pub mod addon_config;
+pub mod certificate;
 pub mod cluster;
 pub mod cluster_class;
 pub mod cluster_group;
+pub mod conditions;
 pub mod controller;
+pub mod dry_run;
 pub mod helm;
+pub mod import_progress;
+pub mod label_group;
+pub mod maintenance;
+pub mod stores;
@@ -0,0 +1,307 @@
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+use kube::ResourceExt as _;
+use kube::api::{ListParams, PartialObjectMeta};
+use kube::client::scope;
+use kube::runtime::reflector::{ObjectRef, Store};
+
+use crate::api::capi_cluster::Cluster;
+use crate::api::capi_clusterclass::ClusterClass;
+use crate::api::fleet_cluster;
+use crate::api::fleet_clustergroup::{
+    CLUSTER_CLASS_LABEL, CLUSTER_CLASS_NAMESPACE_LABEL, ClusterGroup,
+};
+
+use super::controller::Context;
+
+/// Reflector stores shared with the reconcilers, avoiding API server lookups on every event.
+///
+/// Stores are optional, as only the controllers maintaining them can provide them.
+/// Lookups fall back to the API server otherwise.
+#[derive(Clone, Default)]
+pub struct Stores {
+    /// CAPI clusters, indexed by the `ClusterClass` namespace
+    pub clusters: Option<ClusterIndex>,
+    /// CAPI cluster classes
+    pub cluster_classes: Option<Store<ClusterClass>>,
+    /// Fleet cluster metadata
+    pub fleet_clusters: Option<Store<PartialObjectMeta<fleet_cluster::Cluster>>>,
+    /// Generated label and version `ClusterGroups`
+    pub label_groups: Option<Store<ClusterGroup>>,
+    /// `ClusterGroups` generated for cluster classes
+    pub class_groups: Option<Store<ClusterGroup>>,
+}
+
+/// Cluster store with a secondary index by the referenced `ClusterClass` namespace.
+///
+/// The index follows the store, entries of removed clusters or changed class references
+/// are dropped when the cluster event is indexed.
+#[derive(Clone)]
+pub struct ClusterIndex {
+    store: Store<Cluster>,
+    by_class_namespace: Arc<RwLock<BTreeMap<String, HashSet<ObjectRef<Cluster>>>>>,
+}
+
+impl ClusterIndex {
+    #[must_use]
+    pub fn new(store: Store<Cluster>) -> Self {
+        Self {
+            store,
+            by_class_namespace: Arc::default(),
+        }
+    }
+
+    /// Updates the index entry of the cluster. Should be called for every observed cluster
+    /// event, after the store is updated, so deleted clusters are no longer in the store.
+    pub fn index(&self, cluster: &Cluster) {
+        let Ok(mut index) = self.by_class_namespace.write() else {
+            return;
+        };
+
+        let key = ObjectRef::from_obj(cluster);
+        let class_namespace = self
+            .store
+            .get(&key)
+            .and_then(|cluster| cluster.cluster_class_namespace());
+        index.retain(|namespace, clusters| {
+            if class_namespace.as_ref() != Some(namespace) {
+                clusters.remove(&key);
+            }
+            !clusters.is_empty()
+        });
+
+        if let Some(class_namespace) = class_namespace {
+            index.entry(class_namespace).or_default().insert(key);
+        }
+    }
+
+    /// Clusters referencing a `ClusterClass` in the namespace.
+    #[must_use]
+    pub fn by_class_namespace(&self, class_namespace: &str) -> Vec<Arc<Cluster>> {
+        let Ok(index) = self.by_class_namespace.read() else {
+            return vec![];
+        };
+
+        index
+            .get(class_namespace)
+            .into_iter()
+            .flatten()
+            .filter_map(|cluster| self.store.get(cluster))
+            .filter(|cluster| cluster.cluster_class_namespace().as_deref() == Some(class_namespace))
+            .collect()
+    }
+
+    /// Clusters in the namespace.
+    #[must_use]
+    pub fn in_namespace(&self, namespace: &str) -> Vec<Arc<Cluster>> {
+        self.store
+            .state()
+            .into_iter()
+            .filter(|cluster| cluster.namespace().as_deref() == Some(namespace))
+            .collect()
+    }
+}
+
+impl Context {
+    /// Clusters referencing a `ClusterClass` in the namespace.
+    pub(crate) async fn clusters_by_class_namespace(
+        &self,
+        class_namespace: &str,
+    ) -> kube::Result<Vec<Arc<Cluster>>> {
+        if let Some(clusters) = self.stores.clusters.as_ref() {
+            return Ok(clusters.by_class_namespace(class_namespace));
+        }
+
+        Ok(self
+            .client
+            .list::<Cluster>(&ListParams::default(), &scope::Cluster)
+            .await?
+            .into_iter()
+            .filter(|cluster| cluster.cluster_class_namespace().as_deref() == Some(class_namespace))
+            .map(Arc::new)
+            .collect())
+    }
+
+    /// Clusters in the namespace.
+    pub(crate) async fn clusters_in_namespace(
+        &self,
+        namespace: &str,
+    ) -> kube::Result<Vec<Arc<Cluster>>> {
+        if let Some(clusters) = self.stores.clusters.as_ref() {
+            return Ok(clusters.in_namespace(namespace));
+        }
+
+        Ok(self
+            .client
+            .list::<Cluster>(
+                &ListParams::default(),
+                &scope::Namespace::from(namespace.to_string()),
+            )
+            .await?
+            .into_iter()
+            .map(Arc::new)
+            .collect())
+    }
+
+    /// Fleet cluster metadata in the watched namespaces.
+    pub(crate) async fn fleet_clusters(
+        &self,
+    ) -> kube::Result<Vec<Arc<PartialObjectMeta<fleet_cluster::Cluster>>>> {
+        if let Some(clusters) = self.stores.fleet_clusters.as_ref()
+            && clusters.wait_until_ready().await.is_ok()
+        {
+            return Ok(clusters.state());
+        }
+
+        let mut clusters = vec![];
+        for api in self
+            .flags
+            .watched_apis::<fleet_cluster::Cluster>(&self.client)
+        {
+            let list = api.list_metadata(&ListParams::default()).await?;
+            clusters.extend(list.items.into_iter().map(Arc::new));
+        }
+        Ok(clusters)
+    }
+
+    /// Generated label and version `ClusterGroups` in the watched namespaces.
+    pub(crate) async fn label_groups(&self) -> kube::Result<Vec<Arc<ClusterGroup>>> {
+        if let Some(groups) = self.stores.label_groups.as_ref()
+            && groups.wait_until_ready().await.is_ok()
+        {
+            return Ok(groups.state());
+        }
+
+        let params = ListParams::default().labels_from(&ClusterGroup::label_group_selector());
+        let mut groups = vec![];
+        for api in self.flags.watched_apis::<ClusterGroup>(&self.client) {
+            groups.extend(api.list(&params).await?.items.into_iter().map(Arc::new));
+        }
+        Ok(groups)
+    }
+
+    /// `ClusterGroups` generated for the `ClusterClass` in the watched namespaces.
+    pub(crate) async fn class_groups(
+        &self,
+        class: &ClusterClass,
+    ) -> kube::Result<Vec<Arc<ClusterGroup>>> {
+        let class_ref = ObjectRef::from_obj(class);
+        if let Some(groups) = self.stores.class_groups.as_ref()
+            && groups.wait_until_ready().await.is_ok()
+        {
+            return Ok(groups
+                .state()
+                .into_iter()
+                .filter(|group| group.cluster_class_ref().as_ref() == Some(&class_ref))
+                .collect());
+        }
+
+        let name = class.name_any();
+        let namespace = class.namespace().unwrap_or_default();
+
+        let params = ListParams::default().labels(&format!(
+            "{CLUSTER_CLASS_LABEL}={name},{CLUSTER_CLASS_NAMESPACE_LABEL}={namespace}"
+        ));
+        let mut groups = vec![];
+        for api in self.flags.watched_apis::<ClusterGroup>(&self.client) {
+            groups.extend(api.list(&params).await?.items.into_iter().map(Arc::new));
+        }
+        Ok(groups)
+    }
+
+    /// Looks up the `ClusterClass` in the store, falling back to the API server
+    /// if the class is not yet observed.
+    pub(crate) async fn cluster_class(
+        &self,
+        class: &ObjectRef<ClusterClass>,
+    ) -> kube::Result<Arc<ClusterClass>> {
+        if let Some(class) = self
+            .stores
+            .cluster_classes
+            .as_ref()
+            .and_then(|classes| classes.get(class))
+        {
+            return Ok(class);
+        }
+
+        let api = match class.namespace.as_deref() {
+            Some(namespace) => {
+                kube::Api::<ClusterClass>::namespaced(self.client.clone(), namespace)
+            }
+            None => kube::Api::<ClusterClass>::default_namespaced(self.client.clone()),
+        };
+        Ok(Arc::new(api.get(&class.name).await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use cluster_api_rs::capi_cluster::{ClusterSpec, ClusterTopology, ClusterTopologyClassRef};
+    use kube::runtime::{reflector, watcher::Event};
+
+    use crate::api::capi_cluster::{Cluster, ClusterProxy};
+
+    use super::ClusterIndex;
+
+    fn cluster(name: &str, namespace: &str, class_namespace: &str) -> Cluster {
+        let mut cluster = Cluster::new(
+            name,
+            ClusterProxy {
+                proxy: ClusterSpec {
+                    topology: Some(ClusterTopology {
+                        class_ref: ClusterTopologyClassRef {
+                            name: "class".into(),
+                            namespace: Some(class_namespace.into()),
+                        },
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            },
+        );
+        cluster.metadata.namespace = Some(namespace.into());
+        cluster
+    }
+
+    fn indexed(index: &ClusterIndex) -> usize {
+        let entries = index.by_class_namespace.read().unwrap();
+        entries.values().map(HashSet::len).sum()
+    }
+
+    #[test]
+    fn test_cluster_index() {
+        let (reader, mut writer) = reflector::store();
+        let index = ClusterIndex::new(reader);
+
+        let a = cluster("a", "tenant", "classes");
+        let b = cluster("b", "tenant", "other");
+        for c in [&a, &b] {
+            writer.apply_watcher_event(&Event::Apply(c.clone()));
+            index.index(c);
+        }
+
+        assert_eq!(1, index.by_class_namespace("classes").len());
+        assert_eq!(2, index.in_namespace("tenant").len());
+
+        // Class reference change is not returned from the stale index entry
+        let moved = cluster("a", "tenant", "other");
+        writer.apply_watcher_event(&Event::Apply(moved.clone()));
+        index.index(&moved);
+        assert!(index.by_class_namespace("classes").is_empty());
+        assert_eq!(2, index.by_class_namespace("other").len());
+        assert_eq!(2, indexed(&index));
+
+        writer.apply_watcher_event(&Event::Delete(b.clone()));
+        assert_eq!(1, index.by_class_namespace("other").len());
+
+        // Deleted clusters are removed from the index
+        index.index(&b);
+        assert_eq!(1, indexed(&index));
+        writer.apply_watcher_event(&Event::Delete(moved.clone()));
+        index.index(&moved);
+        assert_eq!(0, indexed(&index));
+    }
+}
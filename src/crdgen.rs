@@ -1,9 +1,53 @@
-use ::controller::api::fleet_addon_config::FleetAddonConfig;
+use ::controller::api::fleet_addon_config::{
+    FleetAddonConfig, FleetAddonConfigSpec, FleetChartValues, FleetSettingsSpec,
+};
+use clap::{Parser, Subcommand};
 use kube::CustomResourceExt;
+use schemars::schema_for;
+
+/// Generates the `FleetAddonConfig` CRD, samples and schemas from code
+#[derive(Parser, Debug)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, Default)]
+enum Command {
+    /// The `FleetAddonConfig` CRD
+    #[default]
+    Crd,
+    /// A `FleetAddonConfig` with all defaults applied
+    Sample,
+    /// JSON schema of the `FleetAddonConfig` spec
+    Schema,
+    /// JSON schema of the Fleet chart values
+    ChartValuesSchema,
+    /// JSON schema of the `fleet-controller` `ConfigMap` data
+    SettingsSchema,
+}
 
 fn main() {
-    print!(
-        "{}",
-        serde_yaml::to_string(&FleetAddonConfig::crd()).unwrap()
-    );
+    let output = match Args::parse().command.unwrap_or_default() {
+        Command::Crd => serde_yaml::to_string(&FleetAddonConfig::crd()).unwrap(),
+        Command::Sample => {
+            let mut config = FleetAddonConfig::default();
+            config.metadata.name = Some("fleet-addon-config".into());
+            serde_yaml::to_string(&config).unwrap()
+        }
+        Command::Schema => {
+            let mut schema = schema_for!(FleetAddonConfigSpec);
+            // The derived schema is named after the kube validation wrapper
+            schema.insert("title".into(), "FleetAddonConfigSpec".into());
+            json(&schema)
+        }
+        Command::ChartValuesSchema => json(&schema_for!(FleetChartValues)),
+        Command::SettingsSchema => json(&schema_for!(FleetSettingsSpec)),
+    };
+
+    print!("{output}");
+}
+
+fn json(schema: &schemars::Schema) -> String {
+    serde_json::to_string_pretty(schema).unwrap() + "\n"
 }
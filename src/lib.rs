@@ -1,9 +1,10 @@
 use std::io;
 
 use controllers::{
-    BundleError, SyncError,
+    BundleError, LabelGroupSyncError, SyncError,
     addon_config::{
-        AddonConfigSyncError, DynamicWatcherError, FleetPatchError, ReconcileConfigSyncError,
+        AddonConfigSyncError, ConfigMapSyncError, DynamicWatcherError, FleetPatchError,
+        ReconcileConfigSyncError,
     },
     helm,
 };
@@ -45,6 +46,9 @@ pub enum Error {
     #[error("Reconcile config sync error: {0}")]
     ReconcileConfigSync(#[from] ReconcileConfigSyncError),
 
+    #[error("Label group sync error: {0}")]
+    LabelGroupSync(#[from] LabelGroupSyncError),
+
     #[error("Namespace trigger error: {0}")]
     TriggerError(#[from] TrySendError<()>),
 
@@ -60,6 +64,21 @@ impl Error {
     pub fn metric_label(&self) -> String {
         format!("{self:?}").to_lowercase()
     }
+
+    /// Checks if the error is caused by an invalid configuration, which retries can't resolve.
+    #[must_use]
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            Error::FleetConfigError(
+                AddonConfigSyncError::Certificate(_)
+                    | AddonConfigSyncError::MaintenanceWindow(_)
+                    | AddonConfigSyncError::VersionResolve(helm::VersionResolveError::Parse(..))
+            ) | Error::FleetChartPatchError(FleetPatchError::ConfigMapSyncError(
+                ConfigMapSyncError::FeatureFlags(_)
+            )) | Error::DynamicWatcherError(DynamicWatcherError::SelectorParseError(_))
+        )
+    }
 }
 
 /// Expose all controller components used by main
@@ -1,8 +1,9 @@
 use actix_web::{
     App, HttpRequest, HttpResponse, HttpServer, Responder, get, middleware, web::Data,
 };
+use controller::controllers::helm::version::server_version;
 pub use controller::{self, State, telemetry};
-use kube::Client;
+use kube::{Client, runtime::reflector};
 use prometheus::{Encoder, TextEncoder};
 
 #[get("/metrics")]
@@ -25,6 +26,11 @@ async fn index(c: Data<State>, _req: HttpRequest) -> impl Responder {
     HttpResponse::Ok().json(&d)
 }
 
+#[get("/dryrun")]
+async fn dry_run(c: Data<State>, _req: HttpRequest) -> impl Responder {
+    HttpResponse::Ok().json(c.dry_run().await)
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     telemetry::init();
@@ -35,35 +41,42 @@ async fn main() -> anyhow::Result<()> {
 
     // Init k8s controller state
     let state = State::new(
-        client
-            .apiserver_version()
-            .await
-            .expect("fetch k8s api server version")
-            .minor
-            .parse()
-            .expect("version parse successfully"),
+        server_version(
+            &client
+                .apiserver_version()
+                .await
+                .expect("fetch k8s api server version"),
+        )
+        .expect("version parse successfully"),
     );
 
-    if state.flags.helm_install {
+    // Start web server
+    let bind_address = state.flags.bind_address.clone();
+    let helm_install = state.flags.helm_install;
+    let server_state = state.clone();
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(Data::new(server_state.clone()))
+            .wrap(middleware::Logger::default().exclude("/health"))
+            .service(index)
+            .service(health)
+            .service(metrics)
+            .service(dry_run)
+    })
+    .bind(bind_address)?
+    .shutdown_timeout(5)
+    .run();
+
+    if helm_install {
         let helm_install_controller = controller::run_fleet_helm_controller(state.clone());
-        tokio::join!(helm_install_controller);
+        tokio::join!(helm_install_controller, server).1?;
     } else {
         let fleet_config_controller = controller::run_fleet_addon_config_controller(state.clone());
-        let cluster_controller = controller::run_cluster_controller(state.clone());
-        let cluster_class_controller = controller::run_cluster_class_controller(state.clone());
-
-        // Start web server
-        let server = HttpServer::new(move || {
-            App::new()
-                .app_data(Data::new(state.clone()))
-                .wrap(middleware::Logger::default().exclude("/health"))
-                .service(index)
-                .service(health)
-                .service(metrics)
-        })
-        .bind("0.0.0.0:8443")?
-        .shutdown_timeout(5)
-        .run();
+        // The ClusterClass store is filled by the ClusterClass controller and read by the cluster controller
+        let (cluster_classes, cluster_class_writer) = reflector::store();
+        let cluster_controller = controller::run_cluster_controller(state.clone(), cluster_classes);
+        let cluster_class_controller =
+            controller::run_cluster_class_controller(state.clone(), cluster_class_writer);
 
         tokio::join!(
             cluster_controller,
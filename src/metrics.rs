@@ -1,14 +1,27 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
 
 use crate::Error;
 use jiff::Timestamp;
+use k8s_openapi::api::core::v1::ObjectReference;
 use kube::{
     Client, ResourceExt,
-    runtime::events::{Recorder, Reporter},
+    runtime::events::{Event, EventType, Recorder, Reporter},
 };
 use prometheus::{HistogramVec, IntCounter, IntCounterVec, Registry, histogram_opts, opts};
 use serde::Serialize;
 use tokio::time::Instant;
+use tracing::debug;
+
+/// Window in which identical events for the same object are published once.
+const EVENT_AGGREGATION_WINDOW: Duration = Duration::from_secs(300);
+/// Number of events a reporter can publish in a burst.
+const EVENT_BURST: f64 = 25.0;
+/// Number of events per second added to the reporter budget.
+const EVENT_REFILL_RATE: f64 = 0.5;
+/// Number of tracked events after which expired entries are dropped.
+const EVENT_CACHE_LIMIT: usize = 1024;
 
 #[derive(Clone)]
 pub struct Metrics {
@@ -87,20 +100,186 @@ pub struct Diagnostics {
     pub last_event: Timestamp,
     #[serde(skip)]
     pub reporter: Reporter,
+    /// Events dropped by the recorder, by suppression reason
+    #[serde(skip)]
+    pub suppressed_events: IntCounterVec,
+    #[serde(skip)]
+    events: Arc<Mutex<EventCache>>,
+    #[serde(skip)]
+    recorder: Arc<OnceLock<Recorder>>,
 }
 
 impl Default for Diagnostics {
     fn default() -> Self {
+        let suppressed_events = IntCounterVec::new(
+            opts!(
+                "caapf_controller_events_suppressed_total",
+                "events dropped by aggregation or rate limiting",
+            ),
+            &["reason"],
+        )
+        .unwrap();
         Self {
             last_event: Timestamp::now(),
             reporter: "caapf-controller".into(),
+            suppressed_events,
+            events: Arc::default(),
+            recorder: Arc::default(),
         }
     }
 }
 
 impl Diagnostics {
-    pub fn recorder(&self, client: Client) -> Recorder {
-        Recorder::new(client, self.reporter.clone())
+    /// Register the suppressed events metric with the provided registry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `prometheus::Error` if the metric is already registered.
+    pub fn register(self, registry: &Registry) -> Result<Self, prometheus::Error> {
+        registry.register(Box::new(self.suppressed_events.clone()))?;
+        Ok(self)
+    }
+
+    /// Returns the event recorder shared by all reconcilers.
+    ///
+    /// Identical events for the same object are aggregated within a time window,
+    /// and the reporter is rate limited.
+    pub fn recorder(&self, client: Client) -> EventRecorder {
+        EventRecorder {
+            recorder: self
+                .recorder
+                .get_or_init(|| Recorder::new(client, self.reporter.clone()))
+                .clone(),
+            reporter: self.reporter.controller.clone(),
+            events: self.events.clone(),
+            suppressed: self.suppressed_events.clone(),
+        }
+    }
+}
+
+/// Event recorder wrapper, dropping repeated events and events over the reporter budget.
+pub struct EventRecorder {
+    recorder: Recorder,
+    reporter: String,
+    events: Arc<Mutex<EventCache>>,
+    suppressed: IntCounterVec,
+}
+
+impl EventRecorder {
+    /// Publishes the event, unless it is suppressed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event is rejected by Kubernetes.
+    pub async fn publish(
+        &self,
+        ev: &Event,
+        reference: &ObjectReference,
+    ) -> Result<(), kube::Error> {
+        let key = EventKey::new(ev, reference);
+        let admission = match self.events.lock() {
+            Ok(mut events) => events.admit(&self.reporter, key, Instant::now()),
+            Err(_) => Admission::Publish,
+        };
+
+        match admission {
+            Admission::Publish => self.recorder.publish(ev, reference).await,
+            suppressed => {
+                debug!("Suppressed {} event `{}`", suppressed.as_str(), ev.reason);
+                self.suppressed
+                    .with_label_values(&[suppressed.as_str()])
+                    .inc();
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Admission {
+    Publish,
+    Aggregated,
+    RateLimited,
+}
+
+impl Admission {
+    fn as_str(self) -> &'static str {
+        match self {
+            Admission::Publish => "published",
+            Admission::Aggregated => "aggregated",
+            Admission::RateLimited => "rate_limited",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct EventKey {
+    type_: EventType,
+    reason: String,
+    action: String,
+    note: Option<String>,
+    kind: Option<String>,
+    namespace: Option<String>,
+    name: Option<String>,
+    uid: Option<String>,
+}
+
+impl EventKey {
+    fn new(ev: &Event, reference: &ObjectReference) -> Self {
+        Self {
+            type_: ev.type_,
+            reason: ev.reason.clone(),
+            action: ev.action.clone(),
+            note: ev.note.clone(),
+            kind: reference.kind.clone(),
+            namespace: reference.namespace.clone(),
+            name: reference.name.clone(),
+            uid: reference.uid.clone(),
+        }
+    }
+}
+
+struct TokenBucket {
+    tokens: f64,
+    refilled: Instant,
+}
+
+#[derive(Default)]
+struct EventCache {
+    published: HashMap<(String, EventKey), Instant>,
+    budgets: HashMap<String, TokenBucket>,
+}
+
+impl EventCache {
+    fn admit(&mut self, reporter: &str, key: EventKey, now: Instant) -> Admission {
+        if self.published.len() > EVENT_CACHE_LIMIT {
+            self.published
+                .retain(|_, at| now.duration_since(*at) < EVENT_AGGREGATION_WINDOW);
+        }
+
+        let key = (reporter.to_string(), key);
+        if self
+            .published
+            .get(&key)
+            .is_some_and(|at| now.duration_since(*at) < EVENT_AGGREGATION_WINDOW)
+        {
+            return Admission::Aggregated;
+        }
+
+        let budget = self.budgets.entry(key.0.clone()).or_insert(TokenBucket {
+            tokens: EVENT_BURST,
+            refilled: now,
+        });
+        let elapsed = now.duration_since(budget.refilled).as_secs_f64();
+        budget.tokens = (budget.tokens + elapsed * EVENT_REFILL_RATE).min(EVENT_BURST);
+        budget.refilled = now;
+        if budget.tokens < 1.0 {
+            return Admission::RateLimited;
+        }
+
+        budget.tokens -= 1.0;
+        self.published.insert(key, now);
+        Admission::Publish
     }
 }
 
@@ -119,3 +298,56 @@ impl Drop for ReconcileMeasurer {
         self.metric.with_label_values::<&str>(&[]).observe(duration);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use k8s_openapi::api::core::v1::ObjectReference;
+    use kube::runtime::events::{Event, EventType};
+    use tokio::time::Instant;
+
+    use super::{Admission, EVENT_AGGREGATION_WINDOW, EventCache, EventKey};
+
+    fn key(name: &str) -> EventKey {
+        EventKey::new(
+            &Event {
+                type_: EventType::Normal,
+                reason: "Updated".into(),
+                note: Some(format!("Updated `{name}`")),
+                action: "Creating".into(),
+                secondary: None,
+            },
+            &ObjectReference {
+                name: Some(name.into()),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_event_admission() {
+        let mut cache = EventCache::default();
+        let now = Instant::now();
+
+        assert_eq!(Admission::Publish, cache.admit("caapf", key("a"), now));
+        assert_eq!(Admission::Aggregated, cache.admit("caapf", key("a"), now));
+
+        // Budget is exhausted by distinct events, and refilled over time
+        let admitted = (0..50)
+            .filter(|i| cache.admit("caapf", key(&i.to_string()), now) == Admission::Publish)
+            .count();
+        assert_eq!(24, admitted);
+        assert_eq!(Admission::RateLimited, cache.admit("caapf", key("b"), now));
+        assert_eq!(Admission::Publish, cache.admit("other", key("b"), now));
+        assert_eq!(
+            Admission::Publish,
+            cache.admit("caapf", key("b"), now + Duration::from_secs(2))
+        );
+
+        assert_eq!(
+            Admission::Publish,
+            cache.admit("caapf", key("a"), now + EVENT_AGGREGATION_WINDOW)
+        );
+    }
+}
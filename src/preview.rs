@@ -0,0 +1,51 @@
+use std::{fs, path::PathBuf};
+
+use ::controller::api::{
+    capi_cluster::Cluster, capi_clusterclass::ClusterClass, fleet_addon_config::FleetAddonConfig,
+    preview::Preview,
+};
+use anyhow::Context as _;
+use clap::Parser;
+use k8s_openapi::api::core::v1::Namespace;
+use serde::de::DeserializeOwned;
+
+/// Renders the Fleet objects generated for a CAPI Cluster, without a cluster connection
+#[derive(Parser, Debug)]
+struct Args {
+    /// CAPI Cluster manifest
+    #[arg(long)]
+    cluster: PathBuf,
+
+    /// Namespace manifest of the Cluster, matched against the namespace selector
+    #[arg(long)]
+    namespace: Option<PathBuf>,
+
+    /// ClusterClass manifest referenced by the Cluster topology
+    #[arg(long)]
+    cluster_class: Option<PathBuf>,
+
+    /// FleetAddonConfig manifest
+    #[arg(long)]
+    config: PathBuf,
+}
+
+fn read<T: DeserializeOwned>(path: &PathBuf) -> anyhow::Result<T> {
+    let manifest =
+        fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    serde_yaml::from_str(&manifest).with_context(|| format!("parsing {}", path.display()))
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let cluster: Cluster = read(&args.cluster)?;
+    let namespace: Option<Namespace> = args.namespace.as_ref().map(read).transpose()?;
+    let class: Option<ClusterClass> = args.cluster_class.as_ref().map(read).transpose()?;
+    let config: FleetAddonConfig = read(&args.config)?;
+
+    print!(
+        "{}",
+        Preview::render(&cluster, namespace.as_ref(), class.as_ref(), &config)?.to_yaml()?
+    );
+    Ok(())
+}